@@ -1,5 +1,5 @@
 use console_engine::pixel;
-use console_engine::{KeyCode, MouseButton};
+use console_engine::{Color, KeyCode, MouseButton};
 
 fn main() {
     // initializes a screen filling the terminal with a target of 30 frames per second
@@ -10,8 +10,6 @@ fn main() {
     let rect_w = 16;
     let rect_h = 4;
     let mut dragging = false;
-    let mut relative_x = 0;
-    let mut relative_y = 0;
 
     // main loop, be aware that you'll have to break it because ctrl+C is captured
     loop {
@@ -23,46 +21,45 @@ fn main() {
         }
         engine.clear_screen();
 
-        // check if the mouse's left button has been pressed
-        let mouse_pos = engine.get_mouse_press(MouseButton::Left);
-        if let Some(mouse_pos) = mouse_pos {
-            // if the mouse position is within the boundaries of the rectangle,
-            // enables dragging mode and register relative position of the mouse
-            if mouse_pos.0 as i32 >= rect_x
-                && mouse_pos.0 as i32 <= rect_x + rect_w
-                && mouse_pos.1 as i32 >= rect_y
-                && mouse_pos.1 as i32 <= rect_y + rect_h
-            {
-                dragging = true;
-                relative_x = mouse_pos.0 as i32 - rect_x;
-                relative_y = mouse_pos.1 as i32 - rect_y;
-            }
+        // check if a drag with the mouse's left button has just started
+        if let Some(drag) = engine.get_drag_start() {
+            // if the drag started within the boundaries of the rectangle, enables dragging mode
+            dragging = drag.button == MouseButton::Left
+                && drag.start.0 as i32 >= rect_x
+                && drag.start.0 as i32 <= rect_x + rect_w
+                && drag.start.1 as i32 >= rect_y
+                && drag.start.1 as i32 <= rect_y + rect_h;
         }
 
-        // check if a mouse button is currently held
-        let mouse_pos = engine.get_mouse_held(MouseButton::Left);
-        if let Some(mouse_pos) = mouse_pos {
-            // if dragging mode is enabled, move the rectangle according to mouse's position
+        // if dragging mode is enabled, move the rectangle along with the mouse
+        if let Some(drag) = engine.get_drag().or(engine.get_drag_end()) {
             if dragging {
-                rect_x = mouse_pos.0 as i32 - relative_x;
-                rect_y = mouse_pos.1 as i32 - relative_y;
+                rect_x += drag.delta.0;
+                rect_y += drag.delta.1;
             }
         }
 
-        // check if the mouse has been released
-        let mouse_pos = engine.get_mouse_released(MouseButton::Left);
-        if mouse_pos.is_some() {
+        // check if the drag has ended
+        if engine.get_drag_end().is_some() {
             // disable dragging mode
             dragging = false;
         }
 
+        // highlight the rectangle when the mouse is over it
+        let hovered = engine.is_mouse_over(rect_x, rect_y, rect_x + rect_w, rect_y + rect_h);
+        let color = if dragging || hovered {
+            Color::Yellow
+        } else {
+            Color::White
+        };
+
         // print the recrangle
         engine.rect(
             rect_x,
             rect_y,
             rect_x + rect_w,
             rect_y + rect_h,
-            pixel::pxl_plain('#'),
+            pixel::pxl('#', Some(color), None, None),
         );
         engine.print(rect_x + 4, rect_y + 2, "Drag me!");
 
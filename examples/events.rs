@@ -27,8 +27,8 @@ fn main() {
             // Mouse has been moved or clicked
             Event::Mouse(mouseevent) => {
                 message = format!(
-                    "Mouse: {:?} ({},{})",
-                    mouseevent.kind, mouseevent.column, mouseevent.row
+                    "Mouse: {:?} ({},{}) clicks: {}",
+                    mouseevent.kind, mouseevent.column, mouseevent.row, mouseevent.clicks
                 );
             }
 
@@ -36,6 +36,16 @@ fn main() {
             Event::Resize(w, h) => {
                 message = format!("Resize: {:?}, {:?}", w, h);
             }
+
+            // Some text has been pasted
+            Event::Paste(text) => {
+                message = format!("Paste: {:?}", text);
+            }
+
+            // The terminal has gained or lost focus
+            Event::Focus(focused) => {
+                message = format!("Focus: {:?}", focused);
+            }
         }
     }
 }
@@ -21,6 +21,11 @@ fn main() {
         match event {
             // A frame has passed
             Event::Frame => {
+                // Copy the value of the field into the clipboard on Ctrl+C / Ctrl+X
+                if let Some(copied) = f_text.take_copied() {
+                    engine.set_clipboard(&copied);
+                }
+
                 // Setup screen and border
                 engine.clear_screen();
                 engine.rect_border(4, 0, 14, 2, BorderStyle::new_light());
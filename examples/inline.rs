@@ -0,0 +1,54 @@
+use console_engine::pixel;
+use console_engine::rect_style::BorderStyle;
+use console_engine::Color;
+use console_engine::KeyCode;
+
+fn main() {
+    // reserves 5 lines below the prompt instead of taking over the entire terminal
+    let mut engine = console_engine::ConsoleEngine::builder()
+        .inline(5)
+        .mouse_capture(false)
+        .target_fps(30)
+        .build()
+        .unwrap();
+    let mut progress = 0;
+    // main loop, be aware that you'll have to break it because ctrl+C is captured
+    loop {
+        engine.wait_frame(); // wait for next frame + capture inputs
+        engine.check_resize(); // follow the terminal width
+        if engine.is_key_pressed(KeyCode::Char('q')) {
+            break;
+        }
+
+        let width = engine.get_width() as i32;
+        let bar_width = width - 4;
+        let filled = bar_width * progress / 100;
+
+        engine.clear_screen();
+        engine.rect_border(0, 0, width - 1, 4, BorderStyle::new_light());
+        engine.print(
+            2,
+            1,
+            &format!("Downloading... {}% (press 'q' to stop)", progress),
+        );
+        engine.fill_rect(2, 3, 1 + bar_width, 3, pixel::pxl('░', None, None, None));
+        if filled > 0 {
+            engine.fill_rect(
+                2,
+                3,
+                1 + filled,
+                3,
+                pixel::pxl('█', Some(Color::Green), None, None),
+            );
+        }
+
+        engine.draw(); // draw the region, the final frame is kept once the engine is dropped
+
+        if progress == 100 {
+            break;
+        }
+        if engine.frame_count % 3 == 0 {
+            progress += 1;
+        }
+    }
+}
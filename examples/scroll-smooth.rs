@@ -56,8 +56,8 @@ fn main() {
             y = 0;
         }
 
-        // is_key_released is windows only as of crossterm 0.26.1
-        // sometimes going the opposite direction will incorrectly trigger this code
+        // releases are only reported as they happen with KeyboardMode::Enhanced,
+        // otherwise a key is released once the terminal stops repeating it
         if engine.is_key_released(KeyCode::Up) && y == -1
             || engine.is_key_released(KeyCode::Down) && y == 1
         {
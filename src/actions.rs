@@ -0,0 +1,650 @@
+//! Named input actions, bound to keys and mouse buttons
+//!
+//! An [ActionMap] gives a name to each action of an application (`"quit"`, `"jump"`...)
+//! and binds it to one or more keys, key + modifiers combinations or mouse buttons.
+//! Once given to the engine with [set_action_map](crate::ConsoleEngine::set_action_map),
+//! actions are checked with [is_action_pressed](crate::ConsoleEngine::is_action_pressed)
+//! instead of checking each key, so users can remap the controls.
+//!
+//! Action maps can be saved to and loaded from a simple config text:
+//!
+//! ```text
+//! # lines starting with '#' are comments
+//! quit = q, Esc, ctrl+c
+//! jump = Space, Up
+//! fire = MouseLeft, shift+Enter
+//! save = ctrl+x ctrl+s
+//! top = g g
+//! ```
+//!
+//! - Each line binds an action to a comma-separated list of bindings.
+//!   An action appearing on several lines gets the bindings of every line.
+//! - A binding is a key or a mouse button, optionally prefixed by modifiers joined with `+`:
+//!   `ctrl`, `alt`, `shift` and `super`.
+//! - Keys are either a single character (`q`, `Q`, `?`) or a key name:
+//!   `Space`, `Comma`, `Plus`, `Enter`, `Esc`, `Backspace`, `Tab`, `BackTab`, `Delete`, `Insert`,
+//!   `Left`, `Right`, `Up`, `Down`, `Home`, `End`, `PageUp`, `PageDown`, `F1` to `F24`,
+//!   `CapsLock`, `ScrollLock`, `NumLock`, `PrintScreen`, `Pause`, `Menu`.
+//!   Modifiers and key names are case insensitive, single characters aren't.
+//! - Mouse buttons are `MouseLeft`, `MouseRight` and `MouseMiddle`.
+//! - Bindings separated by spaces form a sequence of keys, to be pressed one after the other.
+//!   The action is pressed on the frame the last key of the sequence is pressed,
+//!   as long as each key came before the [sequence timeout](crate::ConsoleEngine::set_sequence_timeout).
+//!   Mouse buttons can't be part of a sequence.
+//!
+//! usage:
+//! ```no_run
+//! use console_engine::actions::ActionMap;
+//! use console_engine::KeyCode;
+//!
+//! # let mut engine = console_engine::ConsoleEngine::init(20, 10, 30).unwrap();
+//! let mut actions = ActionMap::load("quit = q, ctrl+c").unwrap();
+//! actions.bind("quit", KeyCode::Esc);
+//! engine.set_action_map(actions);
+//!
+//! loop {
+//!     engine.wait_frame();
+//!     if engine.is_action_pressed("quit") {
+//!         break;
+//!     }
+//! }
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton};
+
+pub(crate) const MODIFIERS: [(KeyModifiers, &str); 4] = [
+    (KeyModifiers::CONTROL, "ctrl"),
+    (KeyModifiers::ALT, "alt"),
+    (KeyModifiers::SHIFT, "shift"),
+    (KeyModifiers::SUPER, "super"),
+];
+
+const KEY_NAMES: [(KeyCode, &str); 24] = [
+    (KeyCode::Char(' '), "Space"),
+    (KeyCode::Char(','), "Comma"),
+    (KeyCode::Char('+'), "Plus"),
+    (KeyCode::Enter, "Enter"),
+    (KeyCode::Esc, "Esc"),
+    (KeyCode::Backspace, "Backspace"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::BackTab, "BackTab"),
+    (KeyCode::Delete, "Delete"),
+    (KeyCode::Insert, "Insert"),
+    (KeyCode::Left, "Left"),
+    (KeyCode::Right, "Right"),
+    (KeyCode::Up, "Up"),
+    (KeyCode::Down, "Down"),
+    (KeyCode::Home, "Home"),
+    (KeyCode::End, "End"),
+    (KeyCode::PageUp, "PageUp"),
+    (KeyCode::PageDown, "PageDown"),
+    (KeyCode::CapsLock, "CapsLock"),
+    (KeyCode::ScrollLock, "ScrollLock"),
+    (KeyCode::NumLock, "NumLock"),
+    (KeyCode::PrintScreen, "PrintScreen"),
+    (KeyCode::Pause, "Pause"),
+    (KeyCode::Menu, "Menu"),
+];
+
+const MOUSE_NAMES: [(MouseButton, &str); 3] = [
+    (MouseButton::Left, "MouseLeft"),
+    (MouseButton::Right, "MouseRight"),
+    (MouseButton::Middle, "MouseMiddle"),
+];
+
+/// An input triggering an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    /// A key, pressed along with the given modifiers
+    Key(KeyCode, KeyModifiers),
+    /// A mouse button, pressed along with the given modifiers
+    Mouse(MouseButton, KeyModifiers),
+}
+
+impl From<KeyCode> for Binding {
+    fn from(key: KeyCode) -> Self {
+        Binding::Key(key, KeyModifiers::NONE)
+    }
+}
+
+impl From<MouseButton> for Binding {
+    fn from(button: MouseButton) -> Self {
+        Binding::Mouse(button, KeyModifiers::NONE)
+    }
+}
+
+impl Binding {
+    /// Checks whenever the binding can be written in the [config format](index.html) and read back
+    ///
+    /// Media keys, modifier keys pressed alone and a few others have no name
+    pub(crate) fn has_name(&self) -> bool {
+        match self {
+            Binding::Key(KeyCode::F(n), _) => (1..=24).contains(n),
+            Binding::Key(key, _) => {
+                KEY_NAMES.iter().any(|(code, _)| code == key)
+                    || matches!(key, KeyCode::Char(c) if !c.is_whitespace() && !c.is_control())
+            }
+            Binding::Mouse(..) => true,
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let modifiers = match self {
+            Binding::Key(_, modifiers) | Binding::Mouse(_, modifiers) => *modifiers,
+        };
+        for (modifier, name) in MODIFIERS {
+            if modifiers.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        match self {
+            Binding::Key(key, _) => match KEY_NAMES.iter().find(|(code, _)| code == key) {
+                Some((_, name)) => write!(f, "{}", name),
+                None => match key {
+                    KeyCode::Char(c) => write!(f, "{}", c),
+                    KeyCode::F(n) => write!(f, "F{}", n),
+                    // media and modifier keys have no name, they can't be loaded back
+                    _ => write!(f, "{:?}", key),
+                },
+            },
+            Binding::Mouse(button, _) => {
+                let (_, name) = MOUSE_NAMES.iter().find(|(b, _)| b == button).unwrap();
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
+
+impl FromStr for Binding {
+    type Err = String;
+
+    /// Parses a binding written like in the [config format](index.html)
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = text.trim().split('+').map(str::trim).collect();
+        // a lone '+' splits into two empty parts
+        let input = match parts.pop() {
+            Some("") if parts.last() == Some(&"") => {
+                parts.pop();
+                "+"
+            }
+            Some(input) => input,
+            None => "",
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for part in parts {
+            match MODIFIERS
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(part))
+            {
+                Some((modifier, _)) => modifiers |= *modifier,
+                None => return Err(format!("unknown modifier `{}` in `{}`", part, text.trim())),
+            }
+        }
+        let mut chars = input.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Binding::Key(KeyCode::Char(c), modifiers));
+        }
+        if let Some((button, _)) = MOUSE_NAMES
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(input))
+        {
+            return Ok(Binding::Mouse(*button, modifiers));
+        }
+        let key = match KEY_NAMES
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(input))
+        {
+            Some((key, _)) => *key,
+            None => match input
+                .strip_prefix(['F', 'f'])
+                .and_then(|n| n.parse::<u8>().ok())
+            {
+                Some(n) if (1..=24).contains(&n) => KeyCode::F(n),
+                _ => return Err(format!("unknown key `{}` in `{}`", input, text.trim())),
+            },
+        };
+        Ok(Binding::Key(key, modifiers))
+    }
+}
+
+/// Named actions and the inputs triggering them, see the [module documentation](index.html)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActionMap {
+    /// actions in the order they have been declared, to be saved the same way
+    actions: Vec<Action>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Action {
+    name: String,
+    bindings: Vec<Binding>,
+    sequences: Vec<Vec<Binding>>,
+}
+
+impl ActionMap {
+    /// Creates an empty action map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds an input to an action, the action is created if it doesn't exist yet
+    ///
+    /// usage:
+    /// ```
+    /// use console_engine::actions::{ActionMap, Binding};
+    /// use console_engine::{KeyCode, KeyModifiers, MouseButton};
+    ///
+    /// let mut actions = ActionMap::new();
+    /// actions.bind("quit", KeyCode::Char('q'));
+    /// actions.bind("quit", Binding::Key(KeyCode::Char('c'), KeyModifiers::CONTROL));
+    /// actions.bind("fire", MouseButton::Left);
+    /// ```
+    pub fn bind(&mut self, action: &str, binding: impl Into<Binding>) {
+        let binding = binding.into();
+        let action = self.action_mut(action);
+        if !action.bindings.contains(&binding) {
+            action.bindings.push(binding);
+        }
+    }
+
+    /// Binds a sequence of keys to an action, the keys have to be pressed one after the other
+    ///
+    /// Mouse buttons can't be part of a sequence, a sequence containing one never triggers its action.
+    /// A sequence of a single key is the same as a [bind](#method.bind) call.
+    ///
+    /// usage:
+    /// ```
+    /// use console_engine::actions::{ActionMap, Binding};
+    /// use console_engine::{KeyCode, KeyModifiers};
+    ///
+    /// let mut actions = ActionMap::new();
+    /// actions.bind_sequence("top", &[KeyCode::Char('g').into(), KeyCode::Char('g').into()]);
+    /// let ctrl_x = Binding::Key(KeyCode::Char('x'), KeyModifiers::CONTROL);
+    /// let ctrl_s = Binding::Key(KeyCode::Char('s'), KeyModifiers::CONTROL);
+    /// actions.bind_sequence("save", &[ctrl_x, ctrl_s]);
+    /// ```
+    pub fn bind_sequence(&mut self, action: &str, sequence: &[Binding]) {
+        match sequence {
+            [] => {}
+            [binding] => self.bind(action, *binding),
+            _ => {
+                let action = self.action_mut(action);
+                if !action.sequences.iter().any(|s| s == sequence) {
+                    action.sequences.push(sequence.to_vec());
+                }
+            }
+        }
+    }
+
+    /// Removes every binding and sequence of an action, the action stays declared
+    pub fn unbind(&mut self, action: &str) {
+        let action = self.action_mut(action);
+        action.bindings.clear();
+        action.sequences.clear();
+    }
+
+    /// Get the inputs bound to an action, empty if the action doesn't exist
+    pub fn get_bindings(&self, action: &str) -> &[Binding] {
+        self.find(action).map_or(&[], |action| &action.bindings)
+    }
+
+    /// Get the key sequences bound to an action, empty if the action doesn't exist
+    pub fn get_sequences(&self, action: &str) -> &[Vec<Binding>] {
+        self.find(action).map_or(&[], |action| &action.sequences)
+    }
+
+    /// Get the name of every action, in the order they have been declared
+    pub fn get_actions(&self) -> Vec<&str> {
+        self.actions
+            .iter()
+            .map(|action| action.name.as_str())
+            .collect()
+    }
+
+    /// Reads an action map from a config text
+    ///
+    /// Returns a message telling which line is invalid if the text can't be parsed
+    pub fn load(config: &str) -> Result<ActionMap, String> {
+        let mut map = ActionMap::new();
+        for (index, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (action, bindings) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `action = bindings`", index + 1))?;
+            let action = action.trim();
+            if action.is_empty() {
+                return Err(format!("line {}: missing action name", index + 1));
+            }
+            map.action_mut(action);
+            for binding in bindings.split(',').filter(|b| !b.trim().is_empty()) {
+                let sequence = binding
+                    .split_whitespace()
+                    .map(Binding::from_str)
+                    .collect::<Result<Vec<Binding>, String>>()
+                    .map_err(|err| format!("line {}: {}", index + 1, err))?;
+                if sequence.len() > 1 && sequence.iter().any(|b| matches!(b, Binding::Mouse(..))) {
+                    return Err(format!(
+                        "line {}: mouse buttons can't be part of the sequence `{}`",
+                        index + 1,
+                        binding.trim()
+                    ));
+                }
+                map.bind_sequence(action, &sequence);
+            }
+        }
+        Ok(map)
+    }
+
+    /// Writes the action map as a config text, which can be read back with [load](#method.load)
+    pub fn save(&self) -> String {
+        let mut config = String::new();
+        for action in self.actions.iter() {
+            let bindings: Vec<String> = action
+                .bindings
+                .iter()
+                .map(Binding::to_string)
+                .chain(action.sequences.iter().map(|sequence| {
+                    let keys: Vec<String> = sequence.iter().map(Binding::to_string).collect();
+                    keys.join(" ")
+                }))
+                .collect();
+            config.push_str(&format!("{} = {}\n", action.name, bindings.join(", ")));
+        }
+        config
+    }
+
+    fn find(&self, action: &str) -> Option<&Action> {
+        self.actions.iter().find(|a| a.name == action)
+    }
+
+    fn action_mut(&mut self, action: &str) -> &mut Action {
+        let index = match self.actions.iter().position(|a| a.name == action) {
+            Some(index) => index,
+            None => {
+                self.actions.push(Action {
+                    name: String::from(action),
+                    bindings: vec![],
+                    sequences: vec![],
+                });
+                self.actions.len() - 1
+            }
+        };
+        &mut self.actions[index]
+    }
+}
+
+/// Keys typed so far towards the sequences of an [ActionMap]
+#[derive(Debug, Default)]
+pub(crate) struct SequenceTracker {
+    pending: Vec<KeyEvent>,
+    /// last frame before the pending keys are forgotten
+    deadline: usize,
+    /// actions whose sequence has been completed during the last frame
+    triggered: Vec<String>,
+}
+
+impl SequenceTracker {
+    /// Feeds the keys pressed during a frame, `timeout` being the number of frames allowed between two keys
+    pub(crate) fn update(
+        &mut self,
+        map: &ActionMap,
+        keys: &[KeyEvent],
+        frame: usize,
+        timeout: usize,
+    ) {
+        self.triggered.clear();
+        if frame > self.deadline {
+            self.pending.clear();
+        }
+        for key in keys {
+            self.pending.push(*key);
+            self.deadline = frame.saturating_add(timeout);
+            // drops the oldest keys until the pending ones start a sequence
+            while !self.pending.is_empty() {
+                let mut started = false;
+                for action in map.actions.iter() {
+                    for sequence in action.sequences.iter() {
+                        if !self.starts(sequence) {
+                            continue;
+                        }
+                        if sequence.len() == self.pending.len() {
+                            if !self.triggered.contains(&action.name) {
+                                self.triggered.push(action.name.clone());
+                            }
+                        } else {
+                            started = true;
+                        }
+                    }
+                }
+                if self.triggered.is_empty() && !started {
+                    self.pending.remove(0);
+                } else {
+                    if !self.triggered.is_empty() {
+                        self.pending.clear();
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Forgets the pending keys, used when the sequences change
+    pub(crate) fn reset(&mut self) {
+        self.pending.clear();
+        self.triggered.clear();
+    }
+
+    pub(crate) fn is_triggered(&self, action: &str) -> bool {
+        self.triggered.iter().any(|name| name == action)
+    }
+
+    /// The pending keys written like in the config format, if any
+    pub(crate) fn get_pending(&self) -> Option<String> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        let keys: Vec<String> = self
+            .pending
+            .iter()
+            .map(|key| Binding::Key(key.code, key.modifiers).to_string())
+            .collect();
+        Some(keys.join(" "))
+    }
+
+    /// checks if the pending keys are the beginning of the sequence
+    fn starts(&self, sequence: &[Binding]) -> bool {
+        sequence.len() >= self.pending.len()
+            && sequence
+                .iter()
+                .zip(self.pending.iter())
+                .all(|(binding, key)| match *binding {
+                    Binding::Key(code, modifiers) => KeyEvent::new(code, modifiers) == *key,
+                    Binding::Mouse(..) => false,
+                })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ActionMap, Binding};
+    use crate::{ConsoleEngine, KeyCode, KeyModifiers, MouseButton};
+    use crossterm::event::MouseEventKind;
+
+    #[test]
+    fn load_and_save() {
+        let config = "\
+# controls
+quit = q, Esc, ctrl+c
+jump = Space, shift+alt+Up
+fire = MouseLeft, Comma, +, ctrl++, F12
+
+jump = w
+";
+        let map = ActionMap::load(config).unwrap();
+        assert_eq!(map.get_actions(), vec!["quit", "jump", "fire"]);
+        assert_eq!(
+            map.get_bindings("quit"),
+            &[
+                Binding::Key(KeyCode::Char('q'), KeyModifiers::NONE),
+                Binding::Key(KeyCode::Esc, KeyModifiers::NONE),
+                Binding::Key(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            ]
+        );
+        assert_eq!(
+            map.get_bindings("jump"),
+            &[
+                Binding::Key(KeyCode::Char(' '), KeyModifiers::NONE),
+                Binding::Key(KeyCode::Up, KeyModifiers::ALT | KeyModifiers::SHIFT),
+                Binding::Key(KeyCode::Char('w'), KeyModifiers::NONE),
+            ]
+        );
+        assert_eq!(
+            map.get_bindings("fire"),
+            &[
+                Binding::Mouse(MouseButton::Left, KeyModifiers::NONE),
+                Binding::Key(KeyCode::Char(','), KeyModifiers::NONE),
+                Binding::Key(KeyCode::Char('+'), KeyModifiers::NONE),
+                Binding::Key(KeyCode::Char('+'), KeyModifiers::CONTROL),
+                Binding::Key(KeyCode::F(12), KeyModifiers::NONE),
+            ]
+        );
+        assert_eq!(map.get_bindings("unknown"), &[]);
+
+        let saved = map.save();
+        assert_eq!(
+            saved,
+            "\
+quit = q, Esc, ctrl+c
+jump = Space, alt+shift+Up, w
+fire = MouseLeft, Comma, Plus, ctrl+Plus, F12
+"
+        );
+        assert_eq!(ActionMap::load(&saved).unwrap(), map);
+    }
+
+    #[test]
+    fn sequences() {
+        let mut map = ActionMap::load("top = g g, Home\nsave = ctrl+x ctrl+s").unwrap();
+        let ctrl_x = Binding::Key(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        let ctrl_s = Binding::Key(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        assert_eq!(map.get_sequences("save"), &[vec![ctrl_x, ctrl_s]]);
+        assert_eq!(map.get_bindings("top"), &[Binding::from(KeyCode::Home)]);
+        map.bind_sequence("quit", &[KeyCode::Char('q').into()]);
+        assert_eq!(
+            map.get_bindings("quit"),
+            &[Binding::from(KeyCode::Char('q'))]
+        );
+        assert_eq!(
+            map.save(),
+            "top = Home, g g\nsave = ctrl+x ctrl+s\nquit = q\n"
+        );
+
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 3, 10).unwrap();
+        engine.set_action_map(map);
+        // two frames allowed between keys
+        engine.set_sequence_timeout(std::time::Duration::from_millis(200));
+
+        input.press_key(KeyCode::Char('g'));
+        engine.wait_frame();
+        assert!(!engine.is_action_pressed("top"));
+        assert_eq!(engine.get_pending_sequence().as_deref(), Some("g"));
+        input.press_key(KeyCode::Char('g'));
+        engine.wait_frame();
+        assert!(engine.is_action_pressed("top"));
+        assert_eq!(engine.get_pending_sequence(), None);
+        engine.wait_frame();
+        assert!(!engine.is_action_pressed("top"));
+
+        // keys not starting a sequence are skipped
+        input.press_key(KeyCode::Char('a'));
+        input.press_key(KeyCode::Char('g'));
+        input.press_key(KeyCode::Char('g'));
+        engine.wait_frame();
+        assert!(engine.is_action_pressed("top"));
+
+        input.press_key_with_modifier(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        engine.wait_frame();
+        assert_eq!(engine.get_pending_sequence().as_deref(), Some("ctrl+x"));
+        engine.wait_frame();
+        input.press_key_with_modifier(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        engine.wait_frame();
+        assert!(engine.is_action_pressed("save"));
+
+        // too late
+        input.press_key_with_modifier(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        engine.wait_frame();
+        engine.wait_frame();
+        engine.wait_frame();
+        input.press_key_with_modifier(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        engine.wait_frame();
+        assert!(!engine.is_action_pressed("save"));
+        assert_eq!(engine.get_pending_sequence(), None);
+    }
+
+    #[test]
+    fn load_errors() {
+        assert_eq!(
+            ActionMap::load("quit = q\njump Space"),
+            Err(String::from("line 2: expected `action = bindings`"))
+        );
+        assert_eq!(
+            ActionMap::load(" = q"),
+            Err(String::from("line 1: missing action name"))
+        );
+        assert_eq!(
+            ActionMap::load("quit = hyper+q"),
+            Err(String::from(
+                "line 1: unknown modifier `hyper` in `hyper+q`"
+            ))
+        );
+        assert_eq!(
+            ActionMap::load("quit = F25"),
+            Err(String::from("line 1: unknown key `F25` in `F25`"))
+        );
+        assert_eq!(
+            ActionMap::load("fire = ctrl+x MouseLeft"),
+            Err(String::from(
+                "line 1: mouse buttons can't be part of the sequence `ctrl+x MouseLeft`"
+            ))
+        );
+    }
+
+    #[test]
+    fn engine_actions() {
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 3, 30).unwrap();
+        let mut actions = ActionMap::load("quit = q, ctrl+c\nfire = MouseLeft").unwrap();
+        actions.bind("fire", KeyCode::Enter);
+        engine.set_action_map(actions);
+
+        input.press_key_with_modifier(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        engine.wait_frame();
+        assert!(engine.is_action_pressed("quit"));
+        assert!(engine.is_action_held("quit"));
+        assert!(!engine.is_action_pressed("fire"));
+        engine.wait_frame();
+        assert!(!engine.is_action_pressed("quit"));
+        assert!(engine.is_action_released("quit"));
+
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 1, 1);
+        engine.wait_frame();
+        assert!(engine.is_action_pressed("fire"));
+        // only the exact modifiers trigger an action
+        input.press_key(KeyCode::Char('c'));
+        engine.wait_frame();
+        assert!(!engine.is_action_pressed("quit"));
+
+        engine.get_action_map_mut().unbind("quit");
+        input.press_key(KeyCode::Char('q'));
+        engine.wait_frame();
+        assert!(!engine.is_action_pressed("quit"));
+        assert!(!engine.is_action_pressed("unknown"));
+    }
+}
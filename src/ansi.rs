@@ -0,0 +1,428 @@
+//! Import of text containing ANSI escape sequences
+//!
+//! Colors and styles set with SGR sequences (`ESC [ ... m`) are applied to the imported characters:
+//! - the 16 standard colors (30-37, 90-97, 40-47, 100-107), 256 colors (`38;5;n`) and truecolor (`38;2;r;g;b`)
+//! - bold, dim, italic, blink, reverse, hidden and strikethrough
+//! - single, double, curly and dotted underlines, with their own color (`58;5;n` or `58;2;r;g;b`)
+//!
+//! Any other escape sequence (cursor movement, line clearing, window title...) is ignored.
+//!
+//! usage:
+//! ```
+//! use console_engine::screen::Screen;
+//!
+//! let output = std::process::Command::new("ls").arg("--color=always").output().unwrap();
+//! let listing = Screen::from_ansi(&String::from_utf8_lossy(&output.stdout));
+//! ```
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crossterm::style::Color;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::color::ANSI_16;
+use crate::pixel::{self, grapheme_width, Pixel, Style, UnderlineStyle};
+use crate::screen::Screen;
+
+/// Number of columns between two tab stops
+const TAB_WIDTH: u32 = 8;
+
+impl Screen {
+    /// Creates a new Screen from text containing ANSI escape sequences
+    ///
+    /// The screen is as wide as the longest line, and as high as the number of lines.
+    /// A trailing newline doesn't add an empty line.
+    ///
+    /// see the [ansi](../ansi/index.html) module for the supported sequences
+    pub fn from_ansi(text: &str) -> Screen {
+        let mut cells = vec![];
+        let mut width = 0;
+        let last_row = parse(text, |x, y, grapheme, pxl| {
+            width = width.max(x + grapheme_width(grapheme) as u32);
+            cells.push((x, y, String::from(grapheme), pxl));
+        });
+        let height = if text.ends_with('\n') {
+            last_row
+        } else {
+            last_row + 1
+        };
+        let mut scr = Screen::new(width, height);
+        for (x, y, grapheme, pxl) in cells {
+            scr.set_grapheme(x as i32, y as i32, &grapheme, pxl);
+        }
+        scr
+    }
+
+    /// Prints text containing ANSI escape sequences at the given position
+    ///
+    /// Each line starts back at the given x position, characters outside of the screen are ignored.
+    ///
+    /// see the [ansi](../ansi/index.html) module for the supported sequences
+    pub fn print_ansi(&mut self, x: i32, y: i32, text: &str) {
+        parse(text, |col, row, grapheme, pxl| {
+            self.set_grapheme(x + col as i32, y + row as i32, grapheme, pxl)
+        });
+    }
+}
+
+/// Current colors and style of the parser
+#[derive(Clone, Copy)]
+struct Graphics {
+    fg: Color,
+    bg: Color,
+    style: Style,
+}
+
+impl Default for Graphics {
+    fn default() -> Self {
+        Self {
+            fg: Color::Reset,
+            bg: Color::Reset,
+            style: Style::default(),
+        }
+    }
+}
+
+/// Reads the text, calling `put` for each visible grapheme with its column, row and a pixel giving its style
+///
+/// Returns the row of the cursor once the whole text has been read
+fn parse(text: &str, mut put: impl FnMut(u32, u32, &str, Pixel)) -> u32 {
+    let mut graphics = Graphics::default();
+    let (mut x, mut y) = (0, 0);
+    // printable characters are gathered so they can be split into graphemes
+    let mut printable = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(chr) = chars.next() {
+        if !chr.is_control() {
+            printable.push(chr);
+            continue;
+        }
+        put_graphemes(&printable, &mut x, y, graphics, &mut put);
+        printable.clear();
+        match chr {
+            '\u{1b}' => {
+                if let Some(params) = read_escape(&mut chars) {
+                    apply_sgr(&mut graphics, &params);
+                }
+            }
+            '\n' => {
+                x = 0;
+                y += 1;
+            }
+            '\r' => x = 0,
+            '\t' => x = (x / TAB_WIDTH + 1) * TAB_WIDTH,
+            _ => {}
+        }
+    }
+    put_graphemes(&printable, &mut x, y, graphics, &mut put);
+    y
+}
+
+/// Puts each grapheme of a text without control characters, starting at the given column
+fn put_graphemes(
+    text: &str,
+    x: &mut u32,
+    y: u32,
+    graphics: Graphics,
+    put: &mut impl FnMut(u32, u32, &str, Pixel),
+) {
+    for grapheme in text.graphemes(true) {
+        let width = grapheme_width(grapheme);
+        // zero-width graphemes can't be stored in a cell of their own
+        if width > 0 {
+            put(
+                *x,
+                y,
+                grapheme,
+                pixel::pxl(
+                    ' ',
+                    Some(graphics.fg),
+                    Some(graphics.bg),
+                    Some(graphics.style),
+                ),
+            );
+            *x += width as u32;
+        }
+    }
+}
+
+/// Consumes an escape sequence, the ESC character being already read
+///
+/// Returns the parameters of SGR sequences, other sequences return `None`
+fn read_escape(chars: &mut Peekable<Chars>) -> Option<String> {
+    match chars.next()? {
+        // Control Sequence Introducer: parameters and intermediate bytes, then a final byte
+        '[' => {
+            let mut params = String::new();
+            for chr in chars.by_ref() {
+                if ('\u{40}'..='\u{7e}').contains(&chr) {
+                    return if chr == 'm' { Some(params) } else { None };
+                }
+                params.push(chr);
+            }
+            None
+        }
+        // Operating System Command, terminated by BEL or ST (ESC \)
+        ']' => {
+            while let Some(chr) = chars.next() {
+                match chr {
+                    '\u{7}' => break,
+                    '\u{1b}' if chars.peek() == Some(&'\\') => {
+                        chars.next();
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            None
+        }
+        // any other sequence is made of a single character after ESC
+        _ => None,
+    }
+}
+
+/// Applies the parameters of a SGR sequence
+fn apply_sgr(graphics: &mut Graphics, params: &str) {
+    // sub-parameters (e.g. `38:2::255:0:0` or `4:3`) are only used by extended colors and underline styles
+    let mut params = params.split(';').map(|param| {
+        param
+            .split(':')
+            .map(|value| value.parse::<u16>().ok())
+            .collect::<Vec<_>>()
+    });
+    while let Some(param) = params.next() {
+        // an empty parameter means 0
+        let code = param[0].unwrap_or(0);
+        match code {
+            0 => *graphics = Graphics::default(),
+            1 => graphics.style.bold = true,
+            2 => graphics.style.dim = true,
+            22 => {
+                graphics.style.bold = false;
+                graphics.style.dim = false;
+            }
+            3 => graphics.style.italic = true,
+            23 => graphics.style.italic = false,
+            4 => {
+                let underline_style = match param.get(1) {
+                    Some(Some(2)) => Some(UnderlineStyle::Double),
+                    Some(Some(3)) => Some(UnderlineStyle::Curly),
+                    // dashed underlines are shown as dotted ones
+                    Some(Some(4)) | Some(Some(5)) => Some(UnderlineStyle::Dotted),
+                    Some(Some(0)) => None,
+                    _ => Some(UnderlineStyle::Single),
+                };
+                graphics.style.underlined = underline_style.is_some();
+                graphics.style.underline_style = underline_style.unwrap_or_default();
+            }
+            21 => {
+                graphics.style.underlined = true;
+                graphics.style.underline_style = UnderlineStyle::Double;
+            }
+            24 => {
+                graphics.style.underlined = false;
+                graphics.style.underline_style = UnderlineStyle::default();
+            }
+            5 | 6 => graphics.style.blink = true,
+            25 => graphics.style.blink = false,
+            7 => graphics.style.reverse = true,
+            27 => graphics.style.reverse = false,
+            8 => graphics.style.hidden = true,
+            28 => graphics.style.hidden = false,
+            9 => graphics.style.strikethrough = true,
+            29 => graphics.style.strikethrough = false,
+            30..=37 => graphics.fg = ANSI_16[code as usize - 30],
+            90..=97 => graphics.fg = ANSI_16[code as usize - 90 + 8],
+            39 => graphics.fg = Color::Reset,
+            40..=47 => graphics.bg = ANSI_16[code as usize - 40],
+            100..=107 => graphics.bg = ANSI_16[code as usize - 100 + 8],
+            49 => graphics.bg = Color::Reset,
+            59 => graphics.style.underline_color = None,
+            38 | 48 | 58 => {
+                let color = if param.len() > 1 {
+                    extended_color_colon(&param[1..])
+                } else {
+                    extended_color(&mut params)
+                };
+                if let Some(color) = color {
+                    match code {
+                        38 => graphics.fg = color,
+                        48 => graphics.bg = color,
+                        _ => graphics.style.underline_color = Some(color),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Reads an extended color written with semicolons: `5;n` or `2;r;g;b`
+///
+/// Values above 255 make the whole color invalid, it is still consumed
+fn extended_color(params: &mut impl Iterator<Item = Vec<Option<u16>>>) -> Option<Color> {
+    let mut next = || params.next().and_then(|param| param[0]);
+    match next()? {
+        5 => Some(Color::AnsiValue(u8::try_from(next()?).ok()?)),
+        2 => {
+            let (r, g, b) = (next()?, next()?, next()?);
+            Some(Color::Rgb {
+                r: u8::try_from(r).ok()?,
+                g: u8::try_from(g).ok()?,
+                b: u8::try_from(b).ok()?,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Reads an extended color written with colons: `5:n`, `2:r:g:b` or `2:colorspace:r:g:b`
+///
+/// Values above 255 make the whole color invalid
+fn extended_color_colon(values: &[Option<u16>]) -> Option<Color> {
+    match values {
+        [Some(5), Some(value)] => Some(Color::AnsiValue(u8::try_from(*value).ok()?)),
+        [Some(2), Some(r), Some(g), Some(b)] | [Some(2), _, Some(r), Some(g), Some(b)] => {
+            Some(Color::Rgb {
+                r: u8::try_from(*r).ok()?,
+                g: u8::try_from(*g).ok()?,
+                b: u8::try_from(*b).ok()?,
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::{Style, UnderlineStyle};
+    use crate::screen::Screen;
+    use crate::Color;
+
+    #[test]
+    fn colors_and_styles() {
+        let scr = Screen::from_ansi(concat!(
+            "\x1b[31ma\x1b[1;42mb\x1b[0mc\n",
+            "\x1b[38;5;208md\x1b[48;2;1;2;3me\x1b[39;49m",
+            "\x1b[38:2::4:5:6mf\x1b[3;4;9mg\x1b[23;24;29;22mh\x1b[97mi"
+        ));
+        assert_eq!((scr.get_width(), scr.get_height()), (6, 2));
+        assert_eq!(scr.get_line(0).unwrap(), "abc   ");
+        assert_eq!(scr.get_line(1).unwrap(), "defghi");
+
+        let pxl = |x, y| scr.get_pxl(x, y).unwrap();
+        assert_eq!(pxl(0, 0).get_colors(), (Color::DarkRed, Color::Reset));
+        assert_eq!(pxl(1, 0).get_colors(), (Color::DarkRed, Color::DarkGreen));
+        assert!(pxl(1, 0).style.bold);
+        assert_eq!(pxl(2, 0).get_colors(), (Color::Reset, Color::Reset));
+        assert_eq!(pxl(2, 0).style, Style::default());
+
+        assert_eq!(pxl(0, 1).fg, Color::AnsiValue(208));
+        assert_eq!(pxl(1, 1).bg, Color::Rgb { r: 1, g: 2, b: 3 });
+        assert_eq!(
+            pxl(2, 1).get_colors(),
+            (Color::Rgb { r: 4, g: 5, b: 6 }, Color::Reset)
+        );
+        assert_eq!(
+            pxl(3, 1).style,
+            Style {
+                italic: true,
+                underlined: true,
+                strikethrough: true,
+                ..Style::default()
+            }
+        );
+        assert_eq!(pxl(4, 1).style, Style::default());
+        assert_eq!(pxl(5, 1).fg, Color::White);
+    }
+
+    #[test]
+    fn out_of_range_colors() {
+        let scr = Screen::from_ansi(concat!(
+            "\x1b[38;5;300ma\x1b[48;2;1;300;3;1mb",
+            "\x1b[38:5:256mc\x1b[38:2::1:2:999md"
+        ));
+        let pxl = |x| scr.get_pxl(x, 0).unwrap();
+        assert_eq!(pxl(0).get_colors(), (Color::Reset, Color::Reset));
+        // the components of the ignored color aren't read as other attributes
+        assert_eq!(pxl(1).get_colors(), (Color::Reset, Color::Reset));
+        assert_eq!(
+            pxl(1).style,
+            Style {
+                bold: true,
+                ..Style::default()
+            }
+        );
+        assert_eq!(pxl(2).get_colors(), (Color::Reset, Color::Reset));
+        assert_eq!(pxl(3).get_colors(), (Color::Reset, Color::Reset));
+    }
+
+    #[test]
+    fn extended_styles() {
+        let scr = Screen::from_ansi(concat!(
+            "\x1b[1;2ma\x1b[22;5;7;8mb\x1b[0;4:3;58;5;9mc",
+            "\x1b[21;58:2::1:2:3md\x1b[24;59;25;27;28me"
+        ));
+        let style = |x| scr.get_pxl(x, 0).unwrap().style;
+        assert_eq!(
+            style(0),
+            Style {
+                bold: true,
+                dim: true,
+                ..Style::default()
+            }
+        );
+        assert_eq!(
+            style(1),
+            Style {
+                blink: true,
+                reverse: true,
+                hidden: true,
+                ..Style::default()
+            }
+        );
+        assert_eq!(
+            style(2),
+            Style {
+                underlined: true,
+                underline_style: UnderlineStyle::Curly,
+                underline_color: Some(Color::AnsiValue(9)),
+                ..Style::default()
+            }
+        );
+        assert_eq!(
+            style(3),
+            Style {
+                underlined: true,
+                underline_style: UnderlineStyle::Double,
+                underline_color: Some(Color::Rgb { r: 1, g: 2, b: 3 }),
+                ..Style::default()
+            }
+        );
+        assert_eq!(style(4), Style::default());
+    }
+
+    #[test]
+    fn command_output() {
+        // typical `ls --color` and `grep --color` output, with line clearing sequences and a trailing newline
+        let scr = Screen::from_ansi(
+            "\x1b[0m\x1b[01;34mdir\x1b[0m\r\n\x1b]8;;file://x\x07link\x1b]8;;\x1b\\\n\tx\x1b[K\n",
+        );
+        assert_eq!((scr.get_width(), scr.get_height()), (9, 3));
+        assert_eq!(scr.get_line(0).unwrap(), "dir      ");
+        assert_eq!(scr.get_line(1).unwrap(), "link     ");
+        assert_eq!(scr.get_line(2).unwrap(), "        x");
+        assert_eq!(scr.get_pxl(0, 0).unwrap().fg, Color::DarkBlue);
+        assert!(scr.get_pxl(0, 0).unwrap().style.bold);
+    }
+
+    #[test]
+    fn print_ansi() {
+        let mut scr = Screen::new(4, 2);
+        scr.print_ansi(2, 0, "\x1b[31mab\ncde");
+        assert_eq!(scr.get_line(0).unwrap(), "  ab");
+        assert_eq!(scr.get_line(1).unwrap(), "  cd");
+        assert_eq!(scr.get_pxl(3, 1).unwrap().fg, Color::DarkRed);
+    }
+}
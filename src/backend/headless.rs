@@ -0,0 +1,785 @@
+use std::collections::VecDeque;
+use std::io::Error as ErrorKind;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind,
+};
+use crossterm::style::{Attribute, Color};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{Backend, CursorShape, InlineRegion, KeyboardMode, TerminalSetup};
+use crate::pixel::{self, Style, UnderlineStyle};
+use crate::screen::Screen;
+
+struct HeadlessState {
+    events: VecDeque<Event>,
+    output: Screen,
+    cursor: (u16, u16),
+    fg: Color,
+    bg: Color,
+    style: Style,
+    link: Option<String>,
+    title: String,
+    visible_cursor: Option<(u16, u16)>,
+    cursor_shape: CursorShape,
+    clipboard: Option<String>,
+    /// clipboard content requested by the engine, not taken yet
+    clipboard_answer: Option<String>,
+    /// keyboard mode supported by the fake terminal
+    keyboard_mode: KeyboardMode,
+}
+
+impl HeadlessState {
+    fn set_underline(&mut self, underline_style: UnderlineStyle) {
+        self.style.underlined = true;
+        self.style.underline_style = underline_style;
+    }
+}
+
+/// Backend that doesn't need any terminal
+///
+/// Events are never read from the user, they are injected through a [HeadlessHandle] instead.
+/// When no event is left, [poll_event](trait.Backend.html#tymethod.poll_event) returns immediately,
+/// so each call to `wait_frame` or `poll` runs a frame without sleeping.
+///
+/// Everything the engine draws is written into an internal Screen, available with [HeadlessHandle::get_output]
+///
+/// usage:
+/// ```
+/// use console_engine::backend::HeadlessBackend;
+/// use console_engine::KeyCode;
+///
+/// let backend = HeadlessBackend::new(20, 10);
+/// let input = backend.handle();
+/// let mut engine = console_engine::ConsoleEngine::init_with_backend(backend, 20, 10, 30).unwrap();
+///
+/// input.press_key(KeyCode::Char('q'));
+/// engine.wait_frame();
+/// assert!(engine.is_key_pressed(KeyCode::Char('q')));
+/// ```
+pub struct HeadlessBackend {
+    state: Arc<Mutex<HeadlessState>>,
+    keyboard_enhancement: bool,
+    inline: Option<InlineRegion>,
+}
+
+impl HeadlessBackend {
+    /// Creates a new headless backend with the given size
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(HeadlessState {
+                events: VecDeque::new(),
+                output: Screen::new(width as u32, height as u32),
+                cursor: (0, 0),
+                fg: Color::Reset,
+                bg: Color::Reset,
+                style: Style::default(),
+                link: None,
+                title: String::new(),
+                visible_cursor: None,
+                cursor_shape: CursorShape::default(),
+                clipboard: None,
+                clipboard_answer: None,
+                keyboard_mode: KeyboardMode::default(),
+            })),
+            keyboard_enhancement: false,
+            inline: None,
+        }
+    }
+
+    /// Places the cursor of the fake terminal, as if a shell had printed some lines before the engine started
+    ///
+    /// Only useful with the [inline](crate::builder::EngineBuilder::inline) mode, which reserves its lines from the cursor
+    pub fn with_cursor(self, x: u16, y: u16) -> Self {
+        self.state.lock().unwrap().cursor = (x, y);
+        self
+    }
+
+    /// Get a handle to inject events into this backend and inspect its output
+    ///
+    /// The handle stays valid after the backend has been given to the engine
+    pub fn handle(&self) -> HeadlessHandle {
+        HeadlessHandle {
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+impl Backend for HeadlessBackend {
+    fn begin(&mut self, setup: &TerminalSetup) -> Result<(), ErrorKind> {
+        self.keyboard_enhancement = setup.keyboard_enhancement;
+        if let Some(height) = setup.inline {
+            let mut state = self.state.lock().unwrap();
+            let (column, mut row) = state.cursor;
+            if column > 0 {
+                row = row.saturating_add(1);
+            }
+            let terminal_width = state.output.get_width() as i32;
+            let terminal_height = state.output.get_height() as u16;
+            let region = InlineRegion::new(row, terminal_height, height);
+            // the lines above the region scroll out of the fake terminal
+            let scrolled = (row - region.origin) as i32;
+            state.output.scroll(0, scrolled, pixel::pxl_plain(' '));
+            for y in region.origin..terminal_height {
+                let blank = pixel::pxl_plain(' ');
+                state
+                    .output
+                    .line(0, y as i32, terminal_width - 1, y as i32, blank);
+            }
+            state.cursor = (0, region.origin);
+            self.inline = Some(region);
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, _setup: &TerminalSetup) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+
+    fn keyboard_mode(&self) -> KeyboardMode {
+        if self.keyboard_enhancement {
+            self.state.lock().unwrap().keyboard_mode
+        } else {
+            KeyboardMode::Legacy
+        }
+    }
+
+    fn size(&self) -> Result<(u16, u16), ErrorKind> {
+        let state = self.state.lock().unwrap();
+        let height = state.output.get_height() as u16;
+        Ok((
+            state.output.get_width() as u16,
+            self.inline
+                .map_or(height, |region| region.height.min(height)),
+        ))
+    }
+
+    fn set_size(&mut self, width: u16, height: u16) -> Result<(), ErrorKind> {
+        if self.inline.is_some() {
+            return Ok(());
+        }
+        self.state
+            .lock()
+            .unwrap()
+            .output
+            .resize(width as u32, height as u32);
+        Ok(())
+    }
+
+    fn set_title(&mut self, title: &str) -> Result<(), ErrorKind> {
+        self.state.lock().unwrap().title = String::from(title);
+        Ok(())
+    }
+
+    fn move_cursor(&mut self, x: u16, y: u16) -> Result<(), ErrorKind> {
+        let origin = self.inline.map_or(0, |region| region.origin);
+        self.state.lock().unwrap().cursor = (x, y.saturating_add(origin));
+        Ok(())
+    }
+
+    fn set_attribute(&mut self, attribute: Attribute) -> Result<(), ErrorKind> {
+        let mut state = self.state.lock().unwrap();
+        match attribute {
+            Attribute::Reset => {
+                state.style = Style::default();
+                state.fg = Color::Reset;
+                state.bg = Color::Reset;
+            }
+            Attribute::Bold => state.style.bold = true,
+            Attribute::NoBold => state.style.bold = false,
+            Attribute::Dim => state.style.dim = true,
+            Attribute::NormalIntensity => {
+                state.style.bold = false;
+                state.style.dim = false;
+            }
+            Attribute::Italic => state.style.italic = true,
+            Attribute::NoItalic => state.style.italic = false,
+            Attribute::Underlined => state.set_underline(UnderlineStyle::Single),
+            Attribute::DoubleUnderlined => state.set_underline(UnderlineStyle::Double),
+            Attribute::Undercurled => state.set_underline(UnderlineStyle::Curly),
+            Attribute::Underdotted => state.set_underline(UnderlineStyle::Dotted),
+            Attribute::NoUnderline => {
+                state.style.underlined = false;
+                state.style.underline_style = UnderlineStyle::default();
+            }
+            Attribute::SlowBlink | Attribute::RapidBlink => state.style.blink = true,
+            Attribute::NoBlink => state.style.blink = false,
+            Attribute::Reverse => state.style.reverse = true,
+            Attribute::NoReverse => state.style.reverse = false,
+            Attribute::Hidden => state.style.hidden = true,
+            Attribute::NoHidden => state.style.hidden = false,
+            Attribute::CrossedOut => state.style.strikethrough = true,
+            Attribute::NotCrossedOut => state.style.strikethrough = false,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn set_colors(&mut self, fg: Color, bg: Color) -> Result<(), ErrorKind> {
+        let mut state = self.state.lock().unwrap();
+        state.fg = fg;
+        state.bg = bg;
+        Ok(())
+    }
+
+    fn set_underline_color(&mut self, color: Color) -> Result<(), ErrorKind> {
+        self.state.lock().unwrap().style.underline_color = (color != Color::Reset).then_some(color);
+        Ok(())
+    }
+
+    fn show_cursor(&mut self, x: u16, y: u16, shape: CursorShape) -> Result<(), ErrorKind> {
+        let origin = self.inline.map_or(0, |region| region.origin);
+        let mut state = self.state.lock().unwrap();
+        state.visible_cursor = Some((x, y.saturating_add(origin)));
+        state.cursor_shape = shape;
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), ErrorKind> {
+        self.state.lock().unwrap().visible_cursor = None;
+        Ok(())
+    }
+
+    fn set_clipboard(&mut self, content: &str) -> Result<(), ErrorKind> {
+        self.state.lock().unwrap().clipboard = Some(String::from(content));
+        Ok(())
+    }
+
+    fn request_clipboard(&mut self) -> Result<(), ErrorKind> {
+        let mut state = self.state.lock().unwrap();
+        state.clipboard_answer = state.clipboard.clone();
+        Ok(())
+    }
+
+    fn take_clipboard(&mut self) -> Option<String> {
+        self.state.lock().unwrap().clipboard_answer.take()
+    }
+
+    fn set_link(&mut self, link: Option<&str>) -> Result<(), ErrorKind> {
+        self.state.lock().unwrap().link = link.map(String::from);
+        Ok(())
+    }
+
+    fn print(&mut self, content: &str) -> Result<(), ErrorKind> {
+        let mut state = self.state.lock().unwrap();
+        for grapheme in content.graphemes(true) {
+            if grapheme.contains('\r') {
+                state.cursor.0 = 0;
+            }
+            if grapheme.contains('\n') {
+                state.cursor.1 = state.cursor.1.saturating_add(1);
+            }
+            if grapheme.contains(['\r', '\n']) {
+                continue;
+            }
+            let (x, y) = state.cursor;
+            let pxl = pixel::pxl(' ', Some(state.fg), Some(state.bg), Some(state.style));
+            state.output.set_grapheme(x as i32, y as i32, grapheme, pxl);
+            let width = pixel::grapheme_width(grapheme);
+            let link = state.link.clone();
+            for i in 0..width.max(1) {
+                state
+                    .output
+                    .set_link(x as i32 + i as i32, y as i32, link.as_deref());
+            }
+            state.cursor.0 = x.saturating_add(width.max(1) as u16);
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+
+    fn poll_event(&mut self, _timeout: Duration) -> Result<Option<Event>, ErrorKind> {
+        let event = self.state.lock().unwrap().events.pop_front();
+        Ok(match &mut self.inline {
+            Some(region) => event.map(|event| region.translate(event)),
+            None => event,
+        })
+    }
+}
+
+/// Handle over a [HeadlessBackend], used to script its inputs and check its output
+#[derive(Clone)]
+pub struct HeadlessHandle {
+    state: Arc<Mutex<HeadlessState>>,
+}
+
+impl HeadlessHandle {
+    /// Queues an event, it will be received by the next `wait_frame` or `poll` call
+    pub fn push_event(&self, event: Event) {
+        self.state.lock().unwrap().events.push_back(event);
+    }
+
+    /// Queues a key press
+    pub fn press_key(&self, key: KeyCode) {
+        self.press_key_with_modifier(key, KeyModifiers::NONE);
+    }
+
+    /// Queues a key + modifier (ctrl, shift...) press
+    pub fn press_key_with_modifier(&self, key: KeyCode, modifier: KeyModifiers) {
+        self.push_event(Event::Key(KeyEvent::new(key, modifier)));
+    }
+
+    /// Queues a key repeat, as sent by terminals when a key is held down
+    pub fn repeat_key(&self, key: KeyCode) {
+        self.push_event(Event::Key(KeyEvent::new_with_kind(
+            key,
+            KeyModifiers::NONE,
+            KeyEventKind::Repeat,
+        )));
+    }
+
+    /// Queues a key release, only sent by terminals using [KeyboardMode::Enhanced]
+    pub fn release_key(&self, key: KeyCode) {
+        self.push_event(Event::Key(KeyEvent::new_with_kind(
+            key,
+            KeyModifiers::NONE,
+            KeyEventKind::Release,
+        )));
+    }
+
+    /// Sets the keyboard mode supported by the fake terminal, [KeyboardMode::Legacy] by default
+    ///
+    /// The engine uses it unless keyboard enhancement has been disabled with the builder
+    pub fn set_keyboard_mode(&self, mode: KeyboardMode) {
+        self.state.lock().unwrap().keyboard_mode = mode;
+    }
+
+    /// Queues a mouse event at the given terminal coordinates
+    pub fn mouse(&self, kind: MouseEventKind, column: u16, row: u16) {
+        self.push_event(Event::Mouse(MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }));
+    }
+
+    /// Queues a focus change of the fake terminal
+    pub fn focus(&self, focused: bool) {
+        self.push_event(if focused {
+            Event::FocusGained
+        } else {
+            Event::FocusLost
+        });
+    }
+
+    /// Queues some pasted text
+    pub fn paste(&self, text: &str) {
+        self.push_event(Event::Paste(String::from(text)));
+    }
+
+    /// Resizes the fake terminal and queues the corresponding resize event
+    pub fn resize(&self, width: u16, height: u16) {
+        let mut state = self.state.lock().unwrap();
+        state.output.resize(width as u32, height as u32);
+        state.events.push_back(Event::Resize(width, height));
+    }
+
+    /// Returns a clone of what has been drawn so far by the engine
+    pub fn get_output(&self) -> Screen {
+        self.state.lock().unwrap().output.clone()
+    }
+
+    /// Returns the last title set by the engine
+    pub fn get_title(&self) -> String {
+        self.state.lock().unwrap().title.clone()
+    }
+
+    /// Returns the content of the clipboard, as set by the engine
+    pub fn get_clipboard(&self) -> Option<String> {
+        self.state.lock().unwrap().clipboard.clone()
+    }
+
+    /// Sets the content of the clipboard, as if the user copied something from another program
+    pub fn set_clipboard(&self, content: &str) {
+        self.state.lock().unwrap().clipboard = Some(String::from(content));
+    }
+
+    /// Returns the position of the cursor if the engine is showing it
+    pub fn get_cursor(&self) -> Option<(u16, u16)> {
+        self.state.lock().unwrap().visible_cursor
+    }
+
+    /// Returns the last cursor shape set by the engine
+    pub fn get_cursor_shape(&self) -> CursorShape {
+        self.state.lock().unwrap().cursor_shape
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HeadlessBackend;
+    use crate::backend::{CursorShape, KeyboardMode};
+    use crate::color::ColorSupport;
+    use crate::pixel::{self, Style, UnderlineStyle};
+    use crate::{Color, ConsoleEngine, KeyCode, KeyEventKind, KeyModifiers, MouseButton};
+    use crossterm::event::MouseEventKind;
+
+    #[test]
+    fn scripted_input() {
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 5, 30).unwrap();
+
+        input.press_key(KeyCode::Char('a'));
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 3, 2);
+        engine.wait_frame();
+        assert_eq!(engine.frame_count, 1);
+        assert!(engine.is_key_pressed(KeyCode::Char('a')));
+        assert_eq!(engine.get_mouse_press(MouseButton::Left), Some((3, 2)));
+
+        input.press_key(KeyCode::Char('a'));
+        engine.wait_frame();
+        assert!(!engine.is_key_pressed(KeyCode::Char('a')));
+        assert!(engine.is_key_held(KeyCode::Char('a')));
+        assert_eq!(engine.get_mouse_press(MouseButton::Left), None);
+
+        input.resize(20, 6);
+        engine.wait_frame();
+        assert_eq!(engine.get_resize(), Some((20, 6)));
+        assert!(engine.check_resize());
+        assert_eq!((engine.get_width(), engine.get_height()), (20, 6));
+    }
+
+    #[test]
+    fn draw_output() {
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 3, 30).unwrap();
+        let bold = Style {
+            bold: true,
+            ..Style::default()
+        };
+
+        engine.print(0, 0, "Hello");
+        engine.set_pxl(2, 1, pixel::pxl('#', Some(Color::Red), None, Some(bold)));
+        engine.draw();
+        assert_eq!(input.get_output().get_line(0).unwrap(), "Hello     ");
+        assert_eq!(input.get_output().get_pxl(2, 1), engine.get_pxl(2, 1));
+
+        // only the difference is drawn on the next frame
+        engine.print(0, 0, "Hi");
+        engine.draw();
+        let output = input.get_output();
+        assert_eq!(output.get_line(0).unwrap(), "Hillo     ");
+        let drawn = output.get_pxl(2, 1).unwrap();
+        assert_eq!(drawn.chr, '#');
+        assert_eq!(drawn.fg, Color::Red);
+        assert!(drawn.style.bold);
+        assert!(!output.get_pxl(3, 1).unwrap().style.bold);
+    }
+
+    #[test]
+    fn narrower_terminal() {
+        let (mut engine, input) = ConsoleEngine::init_headless(4, 2, 30).unwrap();
+        engine.print(0, 0, "ab界\ncdef");
+        engine.draw();
+        input.resize(3, 2);
+        assert!(engine.check_resize());
+        engine.draw();
+        let output = input.get_output();
+        assert_eq!(output.get_line(0).unwrap(), "ab ");
+        assert_eq!(output.get_line(1).unwrap(), "cde");
+    }
+
+    #[test]
+    fn extended_styles_output() {
+        let (mut engine, input) = ConsoleEngine::init_headless(6, 1, 30).unwrap();
+        let styles = [
+            Style {
+                bold: true,
+                dim: true,
+                blink: true,
+                ..Style::default()
+            },
+            // turning bold off keeps the other attributes
+            Style {
+                dim: true,
+                blink: true,
+                ..Style::default()
+            },
+            Style {
+                reverse: true,
+                hidden: true,
+                underlined: true,
+                underline_style: UnderlineStyle::Curly,
+                underline_color: Some(Color::Red),
+                ..Style::default()
+            },
+            Style {
+                underlined: true,
+                underline_style: UnderlineStyle::Dotted,
+                ..Style::default()
+            },
+            Style {
+                underlined: true,
+                underline_style: UnderlineStyle::Double,
+                underline_color: Some(Color::Blue),
+                ..Style::default()
+            },
+            Style::default(),
+        ];
+        for (x, style) in styles.iter().enumerate() {
+            engine.set_pxl(x as i32, 0, pixel::pxl('x', None, None, Some(*style)));
+        }
+        engine.draw();
+        let output = input.get_output();
+        for (x, style) in styles.iter().enumerate() {
+            assert_eq!(output.get_pxl(x as i32, 0).unwrap().style, *style);
+        }
+    }
+
+    #[test]
+    fn link_output() {
+        let (mut engine, input) = ConsoleEngine::init_headless(6, 1, 30).unwrap();
+        engine.print_link(0, 0, "ab", "https://example.com");
+        engine.print(2, 0, "cd");
+        engine.print_link(4, 0, "ef", "https://example.org");
+        engine.draw();
+        let output = input.get_output();
+        for x in 0..6 {
+            assert_eq!(output.get_pxl(x, 0), engine.get_pxl(x, 0));
+        }
+        assert_eq!(output.get_link(5, 0), Some("https://example.org"));
+
+        // only the modified cells are drawn again, with their own link
+        engine.print_link(3, 0, "x", "https://example.net");
+        engine.draw();
+        let output = input.get_output();
+        assert_eq!(output.get_link(3, 0), Some("https://example.net"));
+        assert_eq!(output.get_link(2, 0), None);
+    }
+
+    #[test]
+    fn cursor() {
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 3, 30).unwrap();
+        engine.draw();
+        assert_eq!(input.get_cursor(), None);
+
+        engine.set_cursor(Some((4, 1)));
+        engine.set_cursor_shape(CursorShape::BlinkingBar);
+        engine.draw();
+        assert_eq!(input.get_cursor(), Some((4, 1)));
+        assert_eq!(input.get_cursor_shape(), CursorShape::BlinkingBar);
+
+        // a cursor outside of the screen is hidden
+        engine.set_cursor(Some((10, 1)));
+        engine.draw();
+        assert_eq!(input.get_cursor(), None);
+
+        engine.set_cursor(Some((0, 2)));
+        engine.draw();
+        assert_eq!(input.get_cursor(), Some((0, 2)));
+        engine.set_cursor(None);
+        engine.draw();
+        assert_eq!(input.get_cursor(), None);
+    }
+
+    #[test]
+    fn clipboard() {
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 3, 30).unwrap();
+        engine.set_clipboard("copied");
+        assert_eq!(input.get_clipboard().as_deref(), Some("copied"));
+
+        input.set_clipboard("pasted");
+        engine.wait_frame();
+        assert_eq!(engine.get_clipboard(), None);
+        engine.request_clipboard();
+        engine.wait_frame();
+        assert_eq!(engine.get_clipboard(), Some("pasted"));
+        // the content is only available for one frame
+        engine.wait_frame();
+        assert_eq!(engine.get_clipboard(), None);
+    }
+
+    #[test]
+    fn paste() {
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 3, 30).unwrap();
+        input.paste("hello\n");
+        input.paste("world");
+        engine.wait_frame();
+        assert_eq!(engine.get_paste(), Some("hello\nworld"));
+        // pasted text isn't seen as key presses
+        assert!(!engine.is_key_pressed(KeyCode::Char('h')));
+        engine.wait_frame();
+        assert_eq!(engine.get_paste(), None);
+    }
+
+    #[test]
+    fn focus() {
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 3, 30).unwrap();
+        assert!(engine.is_focused());
+        input.focus(false);
+        engine.wait_frame();
+        assert!(!engine.is_focused());
+        // the focus is kept until the next change
+        engine.wait_frame();
+        assert!(!engine.is_focused());
+        input.focus(false);
+        input.focus(true);
+        engine.wait_frame();
+        assert!(engine.is_focused());
+    }
+
+    #[test]
+    fn legacy_keyboard() {
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 3, 30).unwrap();
+        assert_eq!(engine.get_keyboard_mode(), KeyboardMode::Legacy);
+        input.press_key(KeyCode::Char('a'));
+        engine.wait_frame();
+        assert!(engine.is_key_pressed(KeyCode::Char('a')));
+        input.press_key(KeyCode::Char('a'));
+        engine.wait_frame();
+        assert!(engine.is_key_held(KeyCode::Char('a')));
+        assert!(!engine.is_key_released(KeyCode::Char('a')));
+        // the key isn't repeated anymore
+        engine.wait_frame();
+        assert!(!engine.is_key_held(KeyCode::Char('a')));
+        assert!(engine.is_key_released(KeyCode::Char('a')));
+        engine.wait_frame();
+        assert!(!engine.is_key_released(KeyCode::Char('a')));
+    }
+
+    #[test]
+    fn enhanced_keyboard() {
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 3, 30).unwrap();
+        input.set_keyboard_mode(KeyboardMode::Enhanced);
+        assert_eq!(engine.get_keyboard_mode(), KeyboardMode::Enhanced);
+        input.press_key(KeyCode::Char('a'));
+        engine.wait_frame();
+        assert!(engine.is_key_pressed(KeyCode::Char('a')));
+        assert!(engine.is_key_held(KeyCode::Char('a')));
+
+        // keys stay held until they are released, even without repeats
+        engine.wait_frame();
+        assert!(!engine.is_key_pressed(KeyCode::Char('a')));
+        assert!(engine.is_key_held(KeyCode::Char('a')));
+        input.repeat_key(KeyCode::Char('a'));
+        engine.wait_frame();
+        assert!(!engine.is_key_pressed(KeyCode::Char('a')));
+        assert!(engine.is_key_pressed_with_modifier(
+            KeyCode::Char('a'),
+            KeyModifiers::NONE,
+            KeyEventKind::Repeat
+        ));
+        assert!(engine.is_key_held(KeyCode::Char('a')));
+
+        // released with shift pressed in the meantime
+        input.release_key(KeyCode::Char('A'));
+        engine.wait_frame();
+        assert!(!engine.is_key_held(KeyCode::Char('a')));
+        assert!(engine.is_key_released(KeyCode::Char('a')));
+        engine.wait_frame();
+        assert!(!engine.is_key_released(KeyCode::Char('a')));
+
+        // pressed and released within the same frame
+        input.press_key(KeyCode::Enter);
+        input.release_key(KeyCode::Enter);
+        engine.wait_frame();
+        assert!(engine.is_key_pressed(KeyCode::Enter));
+        assert!(!engine.is_key_held(KeyCode::Enter));
+        assert!(engine.is_key_released(KeyCode::Enter));
+    }
+
+    #[test]
+    fn keyboard_enhancement_disabled() {
+        let backend = HeadlessBackend::new(10, 3);
+        backend.handle().set_keyboard_mode(KeyboardMode::Enhanced);
+        let engine = ConsoleEngine::builder()
+            .backend(backend)
+            .keyboard_enhancement(false)
+            .build()
+            .unwrap();
+        assert_eq!(engine.get_keyboard_mode(), KeyboardMode::Legacy);
+    }
+
+    #[test]
+    fn downsampled_output() {
+        let backend = HeadlessBackend::new(4, 1);
+        let output = backend.handle();
+        let mut engine = ConsoleEngine::builder()
+            .backend(backend)
+            .color_support(ColorSupport::Ansi16)
+            .build()
+            .unwrap();
+        assert_eq!(engine.get_color_support(), ColorSupport::Ansi16);
+
+        let red = Color::Rgb {
+            r: 250,
+            g: 20,
+            b: 10,
+        };
+        engine.set_pxl(
+            0,
+            0,
+            pixel::pxl('#', Some(red), Some(Color::AnsiValue(4)), None),
+        );
+        engine.draw();
+        let drawn = output.get_output().get_pxl(0, 0).unwrap();
+        assert_eq!(drawn.fg, Color::Red);
+        assert_eq!(drawn.bg, Color::DarkBlue);
+        // the engine keeps the original colors
+        assert_eq!(engine.get_pxl(0, 0).unwrap().fg, red);
+    }
+
+    #[test]
+    fn wide_output() {
+        let (mut engine, input) = ConsoleEngine::init_headless(6, 1, 30).unwrap();
+        engine.print(0, 0, "界x");
+        engine.draw();
+        assert_eq!(input.get_output().get_line(0).unwrap(), "界x   ");
+
+        engine.print(1, 0, "ab");
+        engine.draw();
+        assert_eq!(input.get_output().get_line(0).unwrap(), " ab   ");
+
+        // grapheme clusters are drawn as a whole
+        engine.print(3, 0, "🇫🇷e\u{301}");
+        engine.draw();
+        assert_eq!(input.get_output().get_line(0).unwrap(), " ab🇫🇷e\u{301}");
+        assert_eq!(
+            input.get_output().get_line(0),
+            engine.get_screen().get_line(0)
+        );
+    }
+
+    #[test]
+    fn inline_output() {
+        // a prompt has been printed on the last line
+        let backend = HeadlessBackend::new(8, 6).with_cursor(2, 5);
+        let input = backend.handle();
+        let mut engine = ConsoleEngine::builder()
+            .backend(backend)
+            .inline(3)
+            .build()
+            .unwrap();
+        assert_eq!((engine.get_width(), engine.get_height()), (8, 3));
+        engine.print(0, 0, "Hello");
+        engine.draw();
+        // the region is pushed up to fit below the prompt
+        assert_eq!(input.get_output().get_line(3).unwrap(), "Hello   ");
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 1, 4);
+        engine.wait_frame();
+        assert_eq!(engine.get_mouse_press(MouseButton::Left), Some((1, 1)));
+
+        // the region follows the terminal when it shrinks
+        input.resize(8, 2);
+        engine.wait_frame();
+        assert_eq!(engine.get_resize(), Some((8, 2)));
+        assert!(engine.check_resize());
+        engine.print(0, 1, "World");
+        engine.draw();
+        let output = input.get_output();
+        assert_eq!(output.get_line(0).unwrap(), "Hello   ");
+        assert_eq!(output.get_line(1).unwrap(), "World   ");
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 1, 1);
+        engine.wait_frame();
+        assert_eq!(engine.get_mouse_press(MouseButton::Left), Some((1, 1)));
+
+        // and gets its reserved height back when it grows
+        input.resize(8, 6);
+        engine.wait_frame();
+        assert_eq!(engine.get_resize(), Some((8, 3)));
+    }
+}
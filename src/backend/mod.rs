@@ -0,0 +1,905 @@
+//! Output and input backends
+//!
+//! The engine never talks to the terminal directly, it goes through a [`Backend`].
+//! By default, [`CrosstermBackend`] is used and writes to stdout, but you can provide your own
+//! implementation to [`ConsoleEngine::init_with_backend`](crate::ConsoleEngine::init_with_backend)
+//! in order to redirect the output or feed the engine with events coming from elsewhere.
+//!
+//! For testing purposes, [`HeadlessBackend`] runs the engine without any terminal.
+
+use std::io::{stdout, Error as ErrorKind, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::SetCursorStyle;
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use crossterm::style::{self, Attribute, Color};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{execute, queue};
+
+use crate::color::ColorSupport;
+use crate::utils;
+
+mod headless;
+
+pub use headless::HeadlessBackend;
+pub use headless::HeadlessHandle;
+
+/// Describes how the output should be set up when the engine starts
+///
+/// Backends are free to ignore the options that don't make sense for them.
+/// Every option is enabled by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalSetup {
+    /// Draw in the alternate screen, leaving the main screen untouched
+    pub alternate_screen: bool,
+    /// Capture mouse events
+    pub mouse_capture: bool,
+    /// Receive pasted text at once, instead of one key event per character
+    pub bracketed_paste: bool,
+    /// Report when the terminal gains or loses focus
+    pub focus_reporting: bool,
+    /// Ask for key repeats and releases (kitty keyboard protocol), on terminals supporting it
+    pub keyboard_enhancement: bool,
+    /// Hide the cursor while the engine is running, unless it is placed with [ConsoleEngine::set_cursor](crate::ConsoleEngine::set_cursor)
+    pub hide_cursor: bool,
+    /// Reserve the given number of lines below the cursor and draw there instead of using the whole terminal
+    ///
+    /// The scrollback is kept intact and the last frame stays in place once the engine stops.
+    /// `alternate_screen` is ignored when this is set.
+    pub inline: Option<u16>,
+    /// Wrap each frame in a synchronized update so it is displayed at once
+    pub synchronized_update: SynchronizedUpdate,
+    /// Colors the output is able to display, the engine maps the other ones to the nearest supported color
+    pub color_support: ColorSupport,
+}
+
+/// Whether frames are wrapped in the synchronized output protocol (DEC mode 2026)
+///
+/// Terminals supporting it wait for the end of the frame before displaying anything,
+/// which prevents tearing on large redraws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SynchronizedUpdate {
+    /// Enabled only for terminals known to support it
+    #[default]
+    Auto,
+    /// Always enabled, terminals that don't support it should ignore it
+    Always,
+    /// Never enabled
+    Never,
+}
+
+impl SynchronizedUpdate {
+    /// Resolves the setting for the current terminal, from its environment variables
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            SynchronizedUpdate::Auto => detect_synchronized_update(|name| std::env::var(name).ok()),
+            SynchronizedUpdate::Always => true,
+            SynchronizedUpdate::Never => false,
+        }
+    }
+}
+
+/// Shape of the terminal cursor, see [ConsoleEngine::set_cursor_shape](crate::ConsoleEngine::set_cursor_shape)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CursorShape {
+    /// The shape configured by the user in the terminal
+    #[default]
+    Default,
+    Block,
+    Bar,
+    Underline,
+    BlinkingBlock,
+    BlinkingBar,
+    BlinkingUnderline,
+}
+
+impl CursorShape {
+    fn to_crossterm(self) -> SetCursorStyle {
+        match self {
+            CursorShape::Default => SetCursorStyle::DefaultUserShape,
+            CursorShape::Block => SetCursorStyle::SteadyBlock,
+            CursorShape::Bar => SetCursorStyle::SteadyBar,
+            CursorShape::Underline => SetCursorStyle::SteadyUnderScore,
+            CursorShape::BlinkingBlock => SetCursorStyle::BlinkingBlock,
+            CursorShape::BlinkingBar => SetCursorStyle::BlinkingBar,
+            CursorShape::BlinkingUnderline => SetCursorStyle::BlinkingUnderScore,
+        }
+    }
+}
+
+/// How key events are reported, see [ConsoleEngine::get_keyboard_mode](crate::ConsoleEngine::get_keyboard_mode)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyboardMode {
+    /// Only key presses are reported, a key is considered released once it stops being repeated
+    #[default]
+    Legacy,
+    /// Key presses, repeats and releases are reported as they happen
+    Enhanced,
+}
+
+/// Guesses if the terminal supports synchronized updates
+///
+/// There is no reliable way to query it without waiting for an answer from the terminal,
+/// so we only look for terminals known to support it.
+fn detect_synchronized_update(var: impl Fn(&str) -> Option<String>) -> bool {
+    const TERMS: [&str; 6] = [
+        "kitty",
+        "foot",
+        "alacritty",
+        "wezterm",
+        "contour",
+        "ghostty",
+    ];
+    const PROGRAMS: [&str; 5] = ["WezTerm", "iTerm.app", "vscode", "ghostty", "contour"];
+    // Windows Terminal
+    if var("WT_SESSION").is_some() {
+        return true;
+    }
+    if let Some(program) = var("TERM_PROGRAM") {
+        if PROGRAMS.contains(&program.as_str()) {
+            return true;
+        }
+    }
+    match var("TERM") {
+        Some(term) => TERMS.iter().any(|name| term.contains(name)),
+        None => false,
+    }
+}
+
+impl Default for TerminalSetup {
+    fn default() -> Self {
+        Self {
+            alternate_screen: true,
+            mouse_capture: true,
+            bracketed_paste: true,
+            focus_reporting: true,
+            keyboard_enhancement: true,
+            hide_cursor: true,
+            inline: None,
+            synchronized_update: SynchronizedUpdate::Auto,
+            color_support: ColorSupport::Auto,
+        }
+    }
+}
+
+/// Operations the engine needs to display a screen and gather user inputs
+///
+/// Drawing functions are expected to be buffered until [flush](#tymethod.flush) is called,
+/// this is how the engine writes a whole frame at once.
+///
+/// Backends must be `Send`, so the engine can be moved to another thread.
+pub trait Backend: Send {
+    /// Prepares the output for the engine (e.g. raw mode, alternate screen, hidden cursor, ...)
+    fn begin(&mut self, setup: &TerminalSetup) -> Result<(), ErrorKind>;
+
+    /// Restores the output as it was before [begin](#tymethod.begin) was called
+    ///
+    /// Implementations should try to restore as much as possible even if some steps fail
+    fn end(&mut self, setup: &TerminalSetup) -> Result<(), ErrorKind>;
+
+    /// How key events are reported, once [begin](#tymethod.begin) has been called
+    fn keyboard_mode(&self) -> KeyboardMode {
+        KeyboardMode::Legacy
+    }
+
+    /// Get the size of the output, in characters
+    fn size(&self) -> Result<(u16, u16), ErrorKind>;
+
+    /// Ask the output to be resized
+    ///
+    /// Backends that can't be resized may simply ignore this call
+    fn set_size(&mut self, width: u16, height: u16) -> Result<(), ErrorKind>;
+
+    /// Set the title of the output, if it has any
+    fn set_title(&mut self, title: &str) -> Result<(), ErrorKind>;
+
+    /// Moves the cursor to the given coordinates
+    fn move_cursor(&mut self, x: u16, y: u16) -> Result<(), ErrorKind>;
+
+    /// Enables or disables a text attribute for the next written cells
+    fn set_attribute(&mut self, attribute: Attribute) -> Result<(), ErrorKind>;
+
+    /// Set the foreground and background colors for the next written cells
+    fn set_colors(&mut self, fg: Color, bg: Color) -> Result<(), ErrorKind>;
+
+    /// Set the color of the underline for the next written cells, [Color::Reset] using the foreground color
+    ///
+    /// Backends unable to color underlines can ignore it
+    fn set_underline_color(&mut self, _color: Color) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+
+    /// Shows the cursor at the given position with the given shape, once a frame has been drawn
+    ///
+    /// Backends without a visible cursor can ignore it
+    fn show_cursor(&mut self, _x: u16, _y: u16, _shape: CursorShape) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+
+    /// Hides the cursor previously shown with [show_cursor](#method.show_cursor)
+    fn hide_cursor(&mut self) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+
+    /// Opens a hyperlink to the given target for the next written cells, or closes it with `None`
+    ///
+    /// Backends unable to display hyperlinks can ignore it
+    fn set_link(&mut self, _link: Option<&str>) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+
+    /// Copies some text into the system clipboard
+    ///
+    /// Backends without a clipboard can ignore it
+    fn set_clipboard(&mut self, _content: &str) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+
+    /// Asks for the content of the system clipboard, to be returned later by [take_clipboard](#method.take_clipboard)
+    fn request_clipboard(&mut self) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+
+    /// Returns the content of the clipboard received since the last call, following a [request_clipboard](#method.request_clipboard)
+    fn take_clipboard(&mut self) -> Option<String> {
+        None
+    }
+
+    /// Writes some content at the cursor position, and moves the cursor accordingly
+    fn print(&mut self, content: &str) -> Result<(), ErrorKind>;
+
+    /// Sends everything that has been written so far to the output
+    fn flush(&mut self) -> Result<(), ErrorKind>;
+
+    /// Called before a frame is drawn, so the backend can hold the display until the frame is complete
+    fn begin_synchronized_update(&mut self) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+
+    /// Called once a frame has been drawn, right before the [flush](#tymethod.flush)
+    fn end_synchronized_update(&mut self) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+
+    /// Waits at most `timeout` for the next event
+    ///
+    /// Must return `Ok(None)` only when the timeout has been reached without any event.
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>, ErrorKind>;
+}
+
+/// Default backend, writes into stdout and reads events from the terminal
+pub struct CrosstermBackend {
+    stdout: Stdout,
+    /// lines reserved by the inline mode, if any
+    inline: Option<InlineRegion>,
+    /// wrap frames in synchronized updates
+    synchronized: bool,
+    keyboard_mode: KeyboardMode,
+    clipboard: ClipboardReader,
+}
+
+impl CrosstermBackend {
+    pub fn new() -> Self {
+        Self {
+            stdout: stdout(),
+            inline: None,
+            synchronized: false,
+            keyboard_mode: KeyboardMode::Legacy,
+            clipboard: ClipboardReader::default(),
+        }
+    }
+
+    /// Reserves `height` lines starting at the cursor, scrolling the terminal if there isn't enough room below it
+    fn reserve_inline(&mut self, height: u16) -> Result<(), ErrorKind> {
+        let (column, mut row) = crossterm::cursor::position()?;
+        let terminal_height = terminal::size()?.1;
+        // don't draw over a partially written line (e.g. a prompt without a trailing newline)
+        if column > 0 {
+            queue!(self.stdout, style::Print("\r\n"))?;
+            row = row.saturating_add(1);
+        }
+        let region = InlineRegion::new(row, terminal_height, height);
+        // printing newlines scrolls the terminal, which keeps the scrolled lines in the scrollback
+        for _ in 1..region.height {
+            queue!(self.stdout, style::Print("\n"))?;
+        }
+        self.inline = Some(region);
+        queue!(
+            self.stdout,
+            crossterm::cursor::MoveTo(0, region.origin),
+            terminal::Clear(ClearType::FromCursorDown)
+        )
+    }
+
+    /// Restores the terminal from what [begin](trait.Backend.html#tymethod.begin) did with the given setup
+    ///
+    /// Writes directly to stdout, so it can be used when no backend instance is available (e.g. while panicking)
+    pub fn restore(setup: &TerminalSetup) -> Result<(), ErrorKind> {
+        let mut output = stdout();
+        let mut restored = queue!(
+            output,
+            style::SetBackgroundColor(Color::Reset),
+            style::SetForegroundColor(Color::Reset)
+        );
+        if setup.synchronized_update.is_enabled() {
+            // in case we stopped in the middle of a frame
+            restored = restored.and(queue!(output, terminal::EndSynchronizedUpdate));
+        }
+        // the cursor may have been shown and hidden by the engine, whatever the setup
+        restored = restored.and(queue!(
+            output,
+            SetCursorStyle::DefaultUserShape,
+            crossterm::cursor::Show
+        ));
+        if setup.mouse_capture {
+            restored = restored.and(queue!(output, crossterm::event::DisableMouseCapture));
+        }
+        if setup.bracketed_paste {
+            restored = restored.and(queue!(output, crossterm::event::DisableBracketedPaste));
+        }
+        if setup.focus_reporting {
+            restored = restored.and(queue!(output, crossterm::event::DisableFocusChange));
+        }
+        // terminals without the keyboard enhancement ignore this sequence
+        if setup.keyboard_enhancement && !cfg!(windows) {
+            restored = restored.and(queue!(
+                output,
+                crossterm::event::PopKeyboardEnhancementFlags
+            ));
+        }
+        if setup.alternate_screen && setup.inline.is_none() {
+            restored = restored.and(queue!(output, terminal::LeaveAlternateScreen));
+        }
+        restored = restored.and(output.flush());
+        // raw mode must be disabled even if the terminal couldn't be written
+        let raw_mode = terminal::disable_raw_mode();
+        restored.and(raw_mode)
+    }
+}
+
+impl Default for CrosstermBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Backend for CrosstermBackend {
+    fn begin(&mut self, setup: &TerminalSetup) -> Result<(), ErrorKind> {
+        terminal::enable_raw_mode()?;
+        self.synchronized = setup.synchronized_update.is_enabled();
+        if let Some(height) = setup.inline {
+            self.reserve_inline(height)?;
+        } else {
+            if setup.alternate_screen {
+                queue!(self.stdout, terminal::EnterAlternateScreen)?;
+            }
+            queue!(
+                self.stdout,
+                terminal::Clear(ClearType::All),
+                crossterm::cursor::MoveTo(0, 0)
+            )?;
+        }
+        if setup.hide_cursor {
+            queue!(self.stdout, crossterm::cursor::Hide)?;
+        }
+        if setup.mouse_capture {
+            queue!(self.stdout, crossterm::event::EnableMouseCapture)?;
+        }
+        if setup.bracketed_paste {
+            queue!(self.stdout, crossterm::event::EnableBracketedPaste)?;
+        }
+        if setup.focus_reporting {
+            queue!(self.stdout, crossterm::event::EnableFocusChange)?;
+        }
+        self.keyboard_mode = if cfg!(windows) {
+            // the windows console always reports repeats and releases
+            KeyboardMode::Enhanced
+        } else if setup.keyboard_enhancement
+            && terminal::supports_keyboard_enhancement().unwrap_or(false)
+        {
+            queue!(
+                self.stdout,
+                crossterm::event::PushKeyboardEnhancementFlags(
+                    crossterm::event::KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                        | crossterm::event::KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+                )
+            )?;
+            KeyboardMode::Enhanced
+        } else {
+            KeyboardMode::Legacy
+        };
+        self.stdout.flush()
+    }
+
+    fn end(&mut self, setup: &TerminalSetup) -> Result<(), ErrorKind> {
+        let mut ended = Ok(());
+        if let Some(region) = self.inline {
+            // leave the last frame in place and give the shell the line right below it
+            ended = queue!(
+                self.stdout,
+                style::SetAttribute(Attribute::Reset),
+                style::ResetColor,
+                crossterm::cursor::MoveTo(0, region.bottom()),
+                style::Print("\r\n")
+            );
+        }
+        ended.and(Self::restore(setup))
+    }
+
+    fn keyboard_mode(&self) -> KeyboardMode {
+        self.keyboard_mode
+    }
+
+    fn size(&self) -> Result<(u16, u16), ErrorKind> {
+        let size = terminal::size()?;
+        match self.inline {
+            Some(region) => Ok((size.0, region.height.min(size.1))),
+            None => Ok(size),
+        }
+    }
+
+    fn set_size(&mut self, width: u16, height: u16) -> Result<(), ErrorKind> {
+        if self.inline.is_some() {
+            // the terminal isn't ours to resize
+            return Ok(());
+        }
+        execute!(
+            self.stdout,
+            terminal::SetSize(width, height),
+            terminal::SetSize(width, height)
+        )
+    }
+
+    fn set_title(&mut self, title: &str) -> Result<(), ErrorKind> {
+        execute!(self.stdout, terminal::SetTitle(title))
+    }
+
+    fn move_cursor(&mut self, x: u16, y: u16) -> Result<(), ErrorKind> {
+        let origin = self.inline.map_or(0, |region| region.origin);
+        queue!(
+            self.stdout,
+            crossterm::cursor::MoveTo(x, y.saturating_add(origin))
+        )
+    }
+
+    fn set_attribute(&mut self, attribute: Attribute) -> Result<(), ErrorKind> {
+        queue!(self.stdout, style::SetAttribute(attribute))
+    }
+
+    fn set_colors(&mut self, fg: Color, bg: Color) -> Result<(), ErrorKind> {
+        queue!(
+            self.stdout,
+            style::SetForegroundColor(fg),
+            style::SetBackgroundColor(bg)
+        )
+    }
+
+    fn set_underline_color(&mut self, color: Color) -> Result<(), ErrorKind> {
+        queue!(self.stdout, style::SetUnderlineColor(color))
+    }
+
+    fn set_link(&mut self, link: Option<&str>) -> Result<(), ErrorKind> {
+        queue!(self.stdout, style::Print(osc8(link)))
+    }
+
+    fn set_clipboard(&mut self, content: &str) -> Result<(), ErrorKind> {
+        execute!(
+            self.stdout,
+            style::Print(format!(
+                "\x1b]52;c;{}\x1b\\",
+                utils::base64_encode(content.as_bytes())
+            ))
+        )
+    }
+
+    fn request_clipboard(&mut self) -> Result<(), ErrorKind> {
+        self.clipboard.request(Instant::now());
+        execute!(self.stdout, style::Print("\x1b]52;c;?\x1b\\"))
+    }
+
+    fn take_clipboard(&mut self) -> Option<String> {
+        self.clipboard.content.take()
+    }
+
+    fn show_cursor(&mut self, x: u16, y: u16, shape: CursorShape) -> Result<(), ErrorKind> {
+        self.move_cursor(x, y)?;
+        queue!(self.stdout, shape.to_crossterm(), crossterm::cursor::Show)
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), ErrorKind> {
+        queue!(self.stdout, crossterm::cursor::Hide)
+    }
+
+    fn print(&mut self, content: &str) -> Result<(), ErrorKind> {
+        queue!(self.stdout, style::Print(content))
+    }
+
+    fn flush(&mut self) -> Result<(), ErrorKind> {
+        self.stdout.flush()
+    }
+
+    fn begin_synchronized_update(&mut self) -> Result<(), ErrorKind> {
+        if self.synchronized {
+            queue!(self.stdout, terminal::BeginSynchronizedUpdate)?;
+        }
+        Ok(())
+    }
+
+    fn end_synchronized_update(&mut self) -> Result<(), ErrorKind> {
+        if self.synchronized {
+            queue!(self.stdout, terminal::EndSynchronizedUpdate)?;
+        }
+        Ok(())
+    }
+
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>, ErrorKind> {
+        let deadline = Instant::now() + timeout;
+        let event = loop {
+            if !event::poll(deadline.saturating_duration_since(Instant::now()))? {
+                return Ok(None);
+            }
+            let event = event::read()?;
+            if !self.clipboard.consume(&event, Instant::now()) {
+                break event;
+            }
+        };
+        Ok(Some(match &mut self.inline {
+            Some(region) => region.translate(event),
+            None => event,
+        }))
+    }
+}
+
+/// Lines reserved below the cursor by the inline mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct InlineRegion {
+    /// number of lines asked for
+    reserved: u16,
+    /// number of lines in use, at most the height of the terminal
+    height: u16,
+    /// first terminal row of the region
+    origin: u16,
+}
+
+impl InlineRegion {
+    /// Reserves `height` lines from `row`
+    fn new(row: u16, terminal_height: u16, height: u16) -> Self {
+        let mut region = Self {
+            reserved: height,
+            height,
+            origin: row,
+        };
+        region.fit(terminal_height);
+        region
+    }
+
+    /// Keeps the region inside a terminal of the given height
+    fn fit(&mut self, terminal_height: u16) {
+        self.height = self.reserved.clamp(1, terminal_height.max(1));
+        self.origin = inline_origin(self.origin, terminal_height, self.height);
+    }
+
+    /// Last terminal row of the region
+    fn bottom(&self) -> u16 {
+        self.origin + self.height - 1
+    }
+
+    /// Follows the resizes of the terminal and reports coordinates relative to the region
+    fn translate(&mut self, event: Event) -> Event {
+        match event {
+            Event::Mouse(mut mouse) => {
+                mouse.row = mouse.row.saturating_sub(self.origin);
+                Event::Mouse(mouse)
+            }
+            Event::Resize(width, terminal_height) => {
+                self.fit(terminal_height);
+                Event::Resize(width, self.height)
+            }
+            event => event,
+        }
+    }
+}
+
+/// Finds the first row of an inline region of `height` lines reserved from `row`
+///
+/// The region is pushed up when it doesn't fit below `row`
+fn inline_origin(row: u16, terminal_height: u16, height: u16) -> u16 {
+    row.min(terminal_height.saturating_sub(height))
+}
+
+/// Reassembles the answer of the terminal to a clipboard request (OSC 52)
+///
+/// crossterm doesn't know this sequence, so it is read as key presses:
+/// Alt+`]`, the characters of `52;c;<base64>`, then Alt+`\` (ESC `\`), Esc or Ctrl+G (BEL)
+///
+/// Terminals refusing to share the clipboard don't answer at all,
+/// so key presses are only watched for [CLIPBOARD_TIMEOUT] after a request.
+#[derive(Default)]
+struct ClipboardReader {
+    /// time until which the answer to the last request is awaited
+    deadline: Option<Instant>,
+    /// characters of the answer read so far
+    sequence: Option<String>,
+    /// decoded content of the clipboard, not taken yet
+    content: Option<String>,
+}
+
+/// Time given to the terminal to start answering a clipboard request
+///
+/// Some terminals ask the user before sharing the clipboard, which takes a while
+const CLIPBOARD_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl ClipboardReader {
+    fn request(&mut self, now: Instant) {
+        self.deadline = Some(now + CLIPBOARD_TIMEOUT);
+    }
+
+    /// Reads an event, returns `true` if it is part of the answer and must not be given to the engine
+    fn consume(&mut self, event: &Event, now: Instant) -> bool {
+        match self.deadline {
+            // an answer that has already started is read until its end
+            Some(deadline) if now < deadline || self.sequence.is_some() => {}
+            _ => {
+                self.deadline = None;
+                return false;
+            }
+        }
+        let key = match event {
+            Event::Key(key) => key,
+            _ => return false,
+        };
+        match (&mut self.sequence, key.code, key.modifiers) {
+            (None, KeyCode::Char(']'), KeyModifiers::ALT) => {
+                self.sequence = Some(String::new());
+                true
+            }
+            (Some(_), KeyCode::Char('\\'), KeyModifiers::ALT)
+            | (Some(_), KeyCode::Esc, _)
+            | (Some(_), KeyCode::Char('g'), KeyModifiers::CONTROL) => {
+                let sequence = self.sequence.take().unwrap_or_default();
+                if let Some(content) = parse_osc52(&sequence) {
+                    self.content = Some(content);
+                }
+                // even a malformed answer ends the request, nothing else will come
+                self.deadline = None;
+                true
+            }
+            (Some(sequence), KeyCode::Char(chr), modifiers)
+                if (modifiers & !KeyModifiers::SHIFT).is_empty() =>
+            {
+                sequence.push(chr);
+                true
+            }
+            // not an answer after all
+            _ => {
+                self.sequence = None;
+                false
+            }
+        }
+    }
+}
+
+/// Decodes the content of an OSC 52 answer: `52;<selection>;<base64>`
+fn parse_osc52(sequence: &str) -> Option<String> {
+    let mut parts = sequence.splitn(3, ';');
+    if parts.next()? != "52" {
+        return None;
+    }
+    parts.next()?;
+    let content = utils::base64_decode(parts.next()?)?;
+    Some(String::from_utf8_lossy(&content).into_owned())
+}
+
+/// OSC 8 sequence opening a hyperlink to the given target, or closing the current one with `None`
+///
+/// Bytes outside of printable ASCII could end the sequence early, so they are percent-encoded
+pub(crate) fn osc8(link: Option<&str>) -> String {
+    let mut sequence = String::from("\x1b]8;;");
+    for byte in link.unwrap_or_default().bytes() {
+        if (0x20..0x7f).contains(&byte) {
+            sequence.push(byte as char);
+        } else {
+            sequence.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    sequence.push_str("\x1b\\");
+    sequence
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Error as ErrorKind;
+    use std::time::{Duration, Instant};
+
+    use crossterm::event::Event;
+    use crossterm::style::{Attribute, Color};
+
+    use super::{
+        detect_synchronized_update, inline_origin, osc8, Backend, ClipboardReader, InlineRegion,
+        TerminalSetup, CLIPBOARD_TIMEOUT,
+    };
+    use crate::ConsoleEngine;
+
+    /// Backend behaving like a detached terminal: everything fails after `begin`
+    struct BrokenBackend;
+
+    fn broken<T>() -> Result<T, ErrorKind> {
+        Err(ErrorKind::from(std::io::ErrorKind::BrokenPipe))
+    }
+
+    impl Backend for BrokenBackend {
+        fn begin(&mut self, _setup: &TerminalSetup) -> Result<(), ErrorKind> {
+            Ok(())
+        }
+        fn end(&mut self, _setup: &TerminalSetup) -> Result<(), ErrorKind> {
+            broken()
+        }
+        fn size(&self) -> Result<(u16, u16), ErrorKind> {
+            Ok((10, 5))
+        }
+        fn set_size(&mut self, _width: u16, _height: u16) -> Result<(), ErrorKind> {
+            broken()
+        }
+        fn set_title(&mut self, _title: &str) -> Result<(), ErrorKind> {
+            broken()
+        }
+        fn move_cursor(&mut self, _x: u16, _y: u16) -> Result<(), ErrorKind> {
+            broken()
+        }
+        fn set_attribute(&mut self, _attribute: Attribute) -> Result<(), ErrorKind> {
+            broken()
+        }
+        fn set_colors(&mut self, _fg: Color, _bg: Color) -> Result<(), ErrorKind> {
+            broken()
+        }
+        fn print(&mut self, _content: &str) -> Result<(), ErrorKind> {
+            broken()
+        }
+        fn flush(&mut self) -> Result<(), ErrorKind> {
+            broken()
+        }
+        fn poll_event(&mut self, _timeout: Duration) -> Result<Option<Event>, ErrorKind> {
+            broken()
+        }
+    }
+
+    #[test]
+    fn errors_are_propagated() {
+        let mut engine = ConsoleEngine::init_with_backend(BrokenBackend, 10, 5, 30).unwrap();
+        engine.print(0, 0, "Hello");
+        assert_eq!(
+            engine.try_draw().unwrap_err().kind(),
+            std::io::ErrorKind::BrokenPipe
+        );
+        assert!(!engine.try_check_resize().unwrap());
+        engine.set_title("ignored");
+        // dropping the engine must not panic even though the output can't be restored
+        drop(engine);
+    }
+
+    #[test]
+    fn inline_region() {
+        // enough room below the cursor
+        assert_eq!(inline_origin(3, 24, 5), 3);
+        assert_eq!(inline_origin(19, 24, 5), 19);
+        // the terminal scrolls to make room
+        assert_eq!(inline_origin(22, 24, 5), 19);
+        assert_eq!(inline_origin(23, 24, 24), 0);
+
+        let mut region = InlineRegion::new(22, 24, 5);
+        assert_eq!((region.origin, region.height, region.bottom()), (19, 5, 23));
+        region.fit(3);
+        assert_eq!((region.origin, region.height, region.bottom()), (0, 3, 2));
+        region.fit(24);
+        assert_eq!((region.origin, region.height, region.bottom()), (0, 5, 4));
+    }
+
+    #[test]
+    fn synchronized_update_detection() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert!(detect_synchronized_update(env(&[("TERM", "xterm-kitty")])));
+        assert!(detect_synchronized_update(env(&[("TERM", "foot-extra")])));
+        assert!(detect_synchronized_update(env(&[
+            ("TERM", "xterm-256color"),
+            ("TERM_PROGRAM", "WezTerm")
+        ])));
+        assert!(detect_synchronized_update(env(&[("WT_SESSION", "1234")])));
+        assert!(!detect_synchronized_update(env(&[(
+            "TERM",
+            "xterm-256color"
+        )])));
+        assert!(!detect_synchronized_update(env(&[("TERM", "linux")])));
+        assert!(!detect_synchronized_update(env(&[])));
+    }
+
+    #[test]
+    fn hyperlinks() {
+        assert_eq!(
+            osc8(Some("https://example.com/a b")),
+            "\x1b]8;;https://example.com/a b\x1b\\"
+        );
+        assert_eq!(
+            osc8(Some("file:///tmp/é\x07")),
+            "\x1b]8;;file:///tmp/%C3%A9%07\x1b\\"
+        );
+        assert_eq!(osc8(None), "\x1b]8;;\x1b\\");
+    }
+
+    #[test]
+    fn clipboard_answer() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+        let text = |text: &str| {
+            text.chars()
+                .map(|chr| {
+                    let modifiers = if chr.is_ascii_uppercase() {
+                        KeyModifiers::SHIFT
+                    } else {
+                        KeyModifiers::NONE
+                    };
+                    key(KeyCode::Char(chr), modifiers)
+                })
+                .collect::<Vec<_>>()
+        };
+        let now = Instant::now();
+        let mut reader = ClipboardReader::default();
+        // nothing is intercepted without a request
+        assert!(!reader.consume(&key(KeyCode::Char(']'), KeyModifiers::ALT), now));
+
+        reader.request(now);
+        assert!(!reader.consume(&key(KeyCode::Char('a'), KeyModifiers::NONE), now));
+        assert!(reader.consume(&key(KeyCode::Char(']'), KeyModifiers::ALT), now));
+        for event in text("52;c;SGk=") {
+            assert!(reader.consume(&event, now));
+        }
+        assert!(reader.consume(&key(KeyCode::Char('\\'), KeyModifiers::ALT), now));
+        assert_eq!(reader.content.take().as_deref(), Some("Hi"));
+        assert_eq!(reader.deadline, None);
+
+        // interrupted sequences are dropped
+        reader.request(now);
+        assert!(reader.consume(&key(KeyCode::Char(']'), KeyModifiers::ALT), now));
+        assert!(!reader.consume(&key(KeyCode::Up, KeyModifiers::NONE), now));
+        assert_eq!(reader.sequence, None);
+    }
+
+    #[test]
+    fn clipboard_without_answer() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+        let now = Instant::now();
+        let mut reader = ClipboardReader::default();
+
+        // a malformed answer ends the request
+        reader.request(now);
+        assert!(reader.consume(&key(KeyCode::Char(']'), KeyModifiers::ALT), now));
+        assert!(reader.consume(&key(KeyCode::Char('1'), KeyModifiers::NONE), now));
+        assert!(reader.consume(&key(KeyCode::Char('g'), KeyModifiers::CONTROL), now));
+        assert_eq!(reader.deadline, None);
+        assert_eq!(reader.content, None);
+        assert!(!reader.consume(&key(KeyCode::Char(']'), KeyModifiers::ALT), now));
+
+        // the request is forgotten once the terminal had time to answer
+        reader.request(now);
+        let later = now + CLIPBOARD_TIMEOUT;
+        assert!(!reader.consume(&key(KeyCode::Char(']'), KeyModifiers::ALT), later));
+        assert_eq!(reader.deadline, None);
+
+        // but an answer that started in time is read until its end
+        reader.request(now);
+        assert!(reader.consume(&key(KeyCode::Char(']'), KeyModifiers::ALT), now));
+        for chr in "52;c;SGk=".chars() {
+            assert!(reader.consume(&key(KeyCode::Char(chr), KeyModifiers::NONE), later));
+        }
+        assert!(reader.consume(&key(KeyCode::Esc, KeyModifiers::NONE), later));
+        assert_eq!(reader.content.as_deref(), Some("Hi"));
+    }
+}
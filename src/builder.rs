@@ -0,0 +1,227 @@
+//! Engine configuration
+
+use std::io::Error as ErrorKind;
+
+use crate::backend::{Backend, CrosstermBackend, SynchronizedUpdate, TerminalSetup};
+use crate::color::ColorSupport;
+use crate::ConsoleEngine;
+
+/// How the size of the engine's screen is chosen when it starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizePolicy {
+    /// Use the given width and height.
+    /// Fails if the terminal is smaller and can't be resized.
+    Fixed(u32, u32),
+    /// Fill the entire terminal
+    Fill,
+    /// Fill the entire terminal, and fails if it isn't at least of the given width and height
+    FillRequire(u32, u32),
+}
+
+/// Builder for [ConsoleEngine]
+///
+/// Every terminal setting is enabled by default, which makes `EngineBuilder::new().build()`
+/// behave like [init_fill](../struct.ConsoleEngine.html#method.init_fill) with a target of 30 frames per second.
+///
+/// usage:
+/// ```no_run
+/// use console_engine::builder::SizePolicy;
+///
+/// // keep the main screen and our own panic hook, don't capture the mouse
+/// let engine = console_engine::ConsoleEngine::builder()
+///     .size(SizePolicy::Fixed(40, 10))
+///     .target_fps(60)
+///     .alternate_screen(false)
+///     .mouse_capture(false)
+///     .panic_hook(false)
+///     .build()
+///     .unwrap();
+/// ```
+pub struct EngineBuilder {
+    size: SizePolicy,
+    target_fps: u32,
+    setup: TerminalSetup,
+    /// `None` until set, the default depends on the backend
+    panic_hook: Option<bool>,
+    exit_on_panic: Option<bool>,
+    backend: Option<Box<dyn Backend>>,
+}
+
+impl EngineBuilder {
+    pub fn new() -> Self {
+        Self {
+            size: SizePolicy::Fill,
+            target_fps: 30,
+            setup: TerminalSetup::default(),
+            panic_hook: None,
+            exit_on_panic: None,
+            backend: None,
+        }
+    }
+
+    /// Choose how the screen size is determined (default: [SizePolicy::Fill])
+    pub fn size(mut self, size: SizePolicy) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set the target frames per second (default: 30)
+    pub fn target_fps(mut self, target_fps: u32) -> Self {
+        self.target_fps = target_fps;
+        self
+    }
+
+    /// Draw in the alternate screen (default: true)
+    ///
+    /// When disabled, the engine draws over the main screen of the terminal
+    pub fn alternate_screen(mut self, enabled: bool) -> Self {
+        self.setup.alternate_screen = enabled;
+        self
+    }
+
+    /// Render inside `height` lines reserved below the cursor instead of the whole terminal (default: disabled)
+    ///
+    /// The alternate screen isn't used in this mode: the scrollback is kept intact
+    /// and the last frame stays in place once the engine stops.
+    /// The region is pushed up when the terminal shrinks, and uses fewer lines if it gets too small.
+    /// Combine it with [SizePolicy::Fill] to use the whole width of the terminal.
+    pub fn inline(mut self, height: u16) -> Self {
+        self.setup.inline = Some(height);
+        self
+    }
+
+    /// Capture mouse events (default: true)
+    pub fn mouse_capture(mut self, enabled: bool) -> Self {
+        self.setup.mouse_capture = enabled;
+        self
+    }
+
+    /// Receive pasted text at once with [get_paste](crate::ConsoleEngine::get_paste), instead of one key event per character (default: true)
+    pub fn bracketed_paste(mut self, enabled: bool) -> Self {
+        self.setup.bracketed_paste = enabled;
+        self
+    }
+
+    /// Report when the terminal gains or loses focus, see [is_focused](crate::ConsoleEngine::is_focused) (default: true)
+    pub fn focus_reporting(mut self, enabled: bool) -> Self {
+        self.setup.focus_reporting = enabled;
+        self
+    }
+
+    /// Use the kitty keyboard protocol to receive key repeats and releases, when the terminal supports it (default: true)
+    ///
+    /// See [get_keyboard_mode](crate::ConsoleEngine::get_keyboard_mode)
+    pub fn keyboard_enhancement(mut self, enabled: bool) -> Self {
+        self.setup.keyboard_enhancement = enabled;
+        self
+    }
+
+    /// Hide the cursor while the engine is running (default: true)
+    pub fn hide_cursor(mut self, enabled: bool) -> Self {
+        self.setup.hide_cursor = enabled;
+        self
+    }
+
+    /// Wrap each frame in a synchronized update, so terminals display it at once (default: [SynchronizedUpdate::Auto])
+    pub fn synchronized_update(mut self, mode: SynchronizedUpdate) -> Self {
+        self.setup.synchronized_update = mode;
+        self
+    }
+
+    /// Set the colors the terminal is able to display (default: [ColorSupport::Auto])
+    ///
+    /// Colors that can't be displayed are mapped to the nearest supported one when drawing
+    pub fn color_support(mut self, support: ColorSupport) -> Self {
+        self.setup.color_support = support;
+        self
+    }
+
+    /// Install a panic hook restoring the terminal before the panic message is printed
+    /// (default: true, false with a custom [backend](#method.backend))
+    ///
+    /// The previous panic hook is still called afterwards.
+    /// Disable it if you manage the panic hook yourself.
+    pub fn panic_hook(mut self, enabled: bool) -> Self {
+        self.panic_hook = Some(enabled);
+        self
+    }
+
+    /// Exit the process with code 1 once the panic hook has been called
+    /// (default: true, false with a custom [backend](#method.backend))
+    ///
+    /// Has no effect if the [panic_hook](#method.panic_hook) is disabled
+    pub fn exit_on_panic(mut self, enabled: bool) -> Self {
+        self.exit_on_panic = Some(enabled);
+        self
+    }
+
+    /// Use a custom backend instead of the terminal (default: [CrosstermBackend])
+    ///
+    /// Unless they are enabled explicitly, the [panic_hook](#method.panic_hook)
+    /// and [exit_on_panic](#method.exit_on_panic) are then disabled, since there is no terminal to restore.
+    pub fn backend<B: Backend + 'static>(mut self, backend: B) -> Self {
+        self.backend = Some(Box::new(backend));
+        self
+    }
+
+    /// Starts the engine with the current configuration
+    pub fn build(self) -> Result<ConsoleEngine, ErrorKind> {
+        assert!(
+            self.target_fps > 0,
+            "Target FPS needs to be greater than zero."
+        );
+        let terminal = self.backend.is_none();
+        if self.panic_hook.unwrap_or(terminal) {
+            let setup = self.setup;
+            let exit_on_panic = self.exit_on_panic.unwrap_or(terminal);
+            let previous_panic_hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |panic_info| {
+                ConsoleEngine::handle_panic(panic_info, &setup);
+                previous_panic_hook(panic_info);
+                if exit_on_panic {
+                    std::process::exit(1);
+                }
+            }));
+        }
+        let backend = self
+            .backend
+            .unwrap_or_else(|| Box::new(CrosstermBackend::new()));
+        ConsoleEngine::start(backend, self.setup, self.size, self.target_fps)
+    }
+}
+
+impl Default for EngineBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SizePolicy;
+    use crate::backend::HeadlessBackend;
+    use crate::ConsoleEngine;
+
+    #[test]
+    fn size_policies() {
+        let engine = ConsoleEngine::builder()
+            .backend(HeadlessBackend::new(30, 12))
+            .build()
+            .unwrap();
+        assert_eq!((engine.get_width(), engine.get_height()), (30, 12));
+
+        let engine = ConsoleEngine::builder()
+            .backend(HeadlessBackend::new(30, 12))
+            .size(SizePolicy::Fixed(10, 5))
+            .build()
+            .unwrap();
+        assert_eq!((engine.get_width(), engine.get_height()), (10, 5));
+
+        let engine = ConsoleEngine::builder()
+            .backend(HeadlessBackend::new(30, 12))
+            .size(SizePolicy::FillRequire(20, 10))
+            .build()
+            .unwrap();
+        assert_eq!((engine.get_width(), engine.get_height()), (30, 12));
+    }
+}
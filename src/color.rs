@@ -0,0 +1,312 @@
+//! Terminal color capabilities
+//!
+//! Pixels accept any crossterm [Color], but not every terminal can display them all.
+//! The engine maps each color to the nearest one supported by the terminal when drawing.
+
+use crossterm::style::Color;
+
+/// Colors a terminal is able to display
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorSupport {
+    /// Detect the capabilities from the `COLORTERM` and `TERM` environment variables
+    ///
+    /// Terminals are assumed to handle 24-bit colors unless they are known to be limited
+    /// (e.g. `TERM=xterm-256color` or the `linux` console)
+    #[default]
+    Auto,
+    /// 24-bit colors, everything is displayed as is
+    TrueColor,
+    /// The 256 colors xterm palette, `Rgb` colors are mapped to the nearest one
+    Ansi256,
+    /// The 16 standard colors, `Rgb` and `AnsiValue` colors are mapped to the nearest one
+    Ansi16,
+}
+
+impl ColorSupport {
+    /// Resolves [Auto](#variant.Auto) into the capabilities of the current terminal
+    ///
+    /// Other values are returned as is
+    pub fn resolve(self) -> ColorSupport {
+        match self {
+            ColorSupport::Auto => detect(|name| std::env::var(name).ok()),
+            support => support,
+        }
+    }
+
+    /// Maps a color to the nearest one this level of support can display
+    ///
+    /// Named colors and [Color::Reset] are always kept as is.
+    /// [Auto](#variant.Auto) keeps every color, [resolve](#method.resolve) it first to downsample for the current terminal.
+    ///
+    /// usage:
+    /// ```
+    /// use console_engine::color::ColorSupport;
+    /// use console_engine::Color;
+    ///
+    /// let orange = Color::Rgb { r: 255, g: 135, b: 0 };
+    /// assert_eq!(ColorSupport::Ansi256.downsample(orange), Color::AnsiValue(208));
+    /// ```
+    pub fn downsample(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorSupport::Ansi256, Color::Rgb { r, g, b }) => {
+                Color::AnsiValue(nearest_256(r, g, b))
+            }
+            (ColorSupport::Ansi16, Color::Rgb { r, g, b }) => ANSI_16[nearest_16(r, g, b)],
+            (ColorSupport::Ansi16, Color::AnsiValue(value)) => {
+                if value < 16 {
+                    ANSI_16[value as usize]
+                } else {
+                    let (r, g, b) = ansi_to_rgb(value);
+                    ANSI_16[nearest_16(r, g, b)]
+                }
+            }
+            (_, color) => color,
+        }
+    }
+}
+
+/// Terminals known to display only the 16 standard colors
+const LIMITED_TERMINALS: [&str; 7] = ["linux", "dumb", "ansi", "cons25", "vt100", "vt102", "vt220"];
+
+/// Guesses the color support of the terminal from its environment variables
+fn detect(var: impl Fn(&str) -> Option<String>) -> ColorSupport {
+    if let Some(colorterm) = var("COLORTERM") {
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorSupport::TrueColor;
+        }
+    }
+    // Windows Terminal doesn't set COLORTERM
+    if var("WT_SESSION").is_some() {
+        return ColorSupport::TrueColor;
+    }
+    match var("TERM") {
+        Some(term) if term.contains("256color") => ColorSupport::Ansi256,
+        Some(term)
+            if term.ends_with("-16color")
+                || term.ends_with("-8color")
+                || term.ends_with("-color")
+                || LIMITED_TERMINALS.contains(&term.as_str()) =>
+        {
+            ColorSupport::Ansi16
+        }
+        // most terminals handle 24-bit colors without telling it
+        _ => ColorSupport::TrueColor,
+    }
+}
+
+/// The 16 standard colors, ordered by their ANSI value
+pub(crate) const ANSI_16: [Color; 16] = [
+    Color::Black,
+    Color::DarkRed,
+    Color::DarkGreen,
+    Color::DarkYellow,
+    Color::DarkBlue,
+    Color::DarkMagenta,
+    Color::DarkCyan,
+    Color::Grey,
+    Color::DarkGrey,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+];
+
+/// RGB values of the 16 standard colors, as displayed by xterm
+const ANSI_16_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Intensity of each step of the 6x6x6 color cube of the 256 colors palette
+const CUBE_STEPS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let dr = a.0 as i32 - b.0 as i32;
+    let dg = a.1 as i32 - b.1 as i32;
+    let db = a.2 as i32 - b.2 as i32;
+    (dr * dr + dg * dg + db * db) as u32
+}
+
+fn nearest_16(r: u8, g: u8, b: u8) -> usize {
+    (0..16)
+        .min_by_key(|&index| distance((r, g, b), ANSI_16_RGB[index]))
+        .unwrap()
+}
+
+/// Index of the nearest step of the color cube for a single channel
+fn cube_index(value: u8) -> usize {
+    CUBE_STEPS
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, &step)| (step as i32 - value as i32).abs())
+        .map(|(index, _)| index)
+        .unwrap()
+}
+
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    // closest color of the cube
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let cube = (CUBE_STEPS[ri], CUBE_STEPS[gi], CUBE_STEPS[bi]);
+    let cube_value = 16 + 36 * ri + 6 * gi + bi;
+    // closest color of the grayscale ramp (8, 18, ..., 238)
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + 10 * gray_index;
+    let gray = (gray_level, gray_level, gray_level);
+    if distance((r, g, b), gray) < distance((r, g, b), cube) {
+        232 + gray_index
+    } else {
+        cube_value as u8
+    }
+}
+
+/// RGB value of a color, as displayed by xterm
+///
+/// Returns `None` for [Color::Reset] since it depends on the terminal
+pub(crate) fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Reset => None,
+        Color::Rgb { r, g, b } => Some((r, g, b)),
+        Color::AnsiValue(value) => Some(ansi_to_rgb(value)),
+        named => ANSI_16
+            .iter()
+            .position(|&color| color == named)
+            .map(|index| ANSI_16_RGB[index]),
+    }
+}
+
+/// RGB value of a color from the 256 colors palette
+fn ansi_to_rgb(value: u8) -> (u8, u8, u8) {
+    match value {
+        0..=15 => ANSI_16_RGB[value as usize],
+        16..=231 => {
+            let index = value as usize - 16;
+            (
+                CUBE_STEPS[index / 36],
+                CUBE_STEPS[(index / 6) % 6],
+                CUBE_STEPS[index % 6],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (value - 232);
+            (level, level, level)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{detect, ColorSupport};
+    use crossterm::style::Color;
+
+    #[test]
+    fn detection() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(
+            detect(env(&[("COLORTERM", "truecolor"), ("TERM", "xterm")])),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect(env(&[("TERM", "xterm-direct")])),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect(env(&[("TERM", "screen-256color")])),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(detect(env(&[("TERM", "linux")])), ColorSupport::Ansi16);
+        assert_eq!(detect(env(&[("TERM", "vt100")])), ColorSupport::Ansi16);
+        assert_eq!(
+            detect(env(&[("TERM", "xterm-16color")])),
+            ColorSupport::Ansi16
+        );
+        // unknown terminals keep every color
+        assert_eq!(detect(env(&[("TERM", "xterm")])), ColorSupport::TrueColor);
+        assert_eq!(detect(env(&[])), ColorSupport::TrueColor);
+        assert_eq!(
+            ColorSupport::Auto.downsample(Color::Rgb { r: 1, g: 2, b: 3 }),
+            Color::Rgb { r: 1, g: 2, b: 3 }
+        );
+    }
+
+    #[test]
+    fn downsampling() {
+        let rgb = |r, g, b| Color::Rgb { r, g, b };
+        // truecolor keeps everything
+        assert_eq!(
+            ColorSupport::TrueColor.downsample(rgb(1, 2, 3)),
+            rgb(1, 2, 3)
+        );
+        // exact matches of the 256 colors palette
+        assert_eq!(
+            ColorSupport::Ansi256.downsample(rgb(255, 135, 0)),
+            Color::AnsiValue(208)
+        );
+        assert_eq!(
+            ColorSupport::Ansi256.downsample(rgb(0, 0, 0)),
+            Color::AnsiValue(16)
+        );
+        assert_eq!(
+            ColorSupport::Ansi256.downsample(rgb(128, 128, 128)),
+            Color::AnsiValue(244)
+        );
+        assert_eq!(
+            ColorSupport::Ansi256.downsample(Color::AnsiValue(42)),
+            Color::AnsiValue(42)
+        );
+        // 16 colors
+        assert_eq!(
+            ColorSupport::Ansi16.downsample(rgb(250, 10, 10)),
+            Color::Red
+        );
+        assert_eq!(
+            ColorSupport::Ansi16.downsample(rgb(20, 20, 20)),
+            Color::Black
+        );
+        assert_eq!(
+            ColorSupport::Ansi16.downsample(rgb(0, 0, 150)),
+            Color::DarkBlue
+        );
+        assert_eq!(
+            ColorSupport::Ansi16.downsample(Color::AnsiValue(9)),
+            Color::Red
+        );
+        assert_eq!(
+            ColorSupport::Ansi16.downsample(Color::AnsiValue(231)),
+            Color::White
+        );
+        assert_eq!(
+            ColorSupport::Ansi16.downsample(Color::AnsiValue(232)),
+            Color::Black
+        );
+        // named colors and reset are untouched
+        assert_eq!(ColorSupport::Ansi16.downsample(Color::Reset), Color::Reset);
+        assert_eq!(
+            ColorSupport::Ansi16.downsample(Color::DarkCyan),
+            Color::DarkCyan
+        );
+    }
+}
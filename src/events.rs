@@ -1,5 +1,5 @@
 #![cfg(feature = "event")]
-use crossterm::event::{KeyEvent, MouseEvent};
+use crossterm::event::{KeyEvent, KeyModifiers, MouseEventKind};
 
 /// # ConsoleEngine events
 ///
@@ -7,7 +7,7 @@ use crossterm::event::{KeyEvent, MouseEvent};
 /// You may want to match the event to act according to which one has been returned
 ///
 /// See example `events`
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Event {
     /// A frame has passed. You can either call `engine.draw()` or do nothing.
     Frame,
@@ -15,8 +15,31 @@ pub enum Event {
     /// You can check which one and if a modifier has been pressed as well.
     Key(KeyEvent),
     /// The Mouse has been moved, or clicked.
-    /// You can check which event occured and if a modifier has been pressed as well.
+    /// You can check which event occured, if a modifier has been pressed as well and if it's a double click.
     Mouse(MouseEvent),
     /// The window has been resized.
     Resize(u16, u16),
+    /// Some text has been pasted into the terminal.
+    Paste(String),
+    /// The terminal has gained (`true`) or lost (`false`) focus.
+    Focus(bool),
+}
+
+/// A mouse event, as reported by the terminal
+///
+/// Button presses also tell how many successive clicks they are part of,
+/// see [set_click_interval](crate::ConsoleEngine::set_click_interval)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseEvent {
+    /// The kind of mouse event that was caused
+    pub kind: MouseEventKind,
+    /// The column that the event occurred on
+    pub column: u16,
+    /// The row that the event occurred on
+    pub row: u16,
+    /// The key modifiers active when the event occurred
+    pub modifiers: KeyModifiers,
+    /// For button presses, 1 for a single click, 2 for a double click, 3 for a triple click...
+    /// 0 for any other event
+    pub clicks: u8,
 }
@@ -0,0 +1,376 @@
+//! HTML and SVG exports of screens
+//!
+//! Both formats are self-contained: colors and styles are written inline,
+//! so the output can be pasted into documentation or attached to bug reports as is.
+//! Blinking text is exported as regular text.
+//!
+//! usage:
+//! ```
+//! use console_engine::screen::Screen;
+//!
+//! let mut scr = Screen::new(10, 2);
+//! scr.print(0, 0, "Hello");
+//! std::fs::write("screen.html", scr.to_html()).unwrap();
+//! std::fs::write("screen.svg", scr.to_svg()).unwrap();
+//! ```
+
+use std::fmt::Write;
+
+use crossterm::style::Color;
+
+use crate::color;
+use crate::pixel::{Style, UnderlineStyle};
+use crate::screen::Screen;
+
+/// Color used for [Color::Reset] foregrounds
+const DEFAULT_FG: &str = "#e5e5e5";
+/// Color used for [Color::Reset] backgrounds
+const DEFAULT_BG: &str = "#000000";
+/// Width of a cell in the SVG export, in pixels
+const CELL_WIDTH: u32 = 10;
+/// Height of a cell in the SVG export, in pixels
+const CELL_HEIGHT: u32 = 20;
+/// Font size of the SVG export, fitting in a cell
+const FONT_SIZE: u32 = 16;
+
+/// Consecutive cells of a row sharing the same colors and style
+struct Run {
+    /// column of the first cell
+    x: u32,
+    /// number of cells covered by the run, including the right half of wide characters
+    cells: u32,
+    text: String,
+    fg: Color,
+    bg: Color,
+    style: Style,
+}
+
+impl Screen {
+    /// Exports the screen as an HTML `<pre>` block, with a styled `<span>` for each run of similar characters
+    pub fn to_html(&self) -> String {
+        let mut html = format!(
+            "<pre style=\"font-family: monospace; color: {}; background-color: {};\">",
+            DEFAULT_FG, DEFAULT_BG
+        );
+        for y in 0..self.get_height() {
+            if y > 0 {
+                html.push('\n');
+            }
+            for run in self.runs(y) {
+                let css = css(&run);
+                if css.is_empty() {
+                    html.push_str(&escape(&run.text));
+                } else {
+                    write!(html, "<span style=\"{}\">{}</span>", css, escape(&run.text)).unwrap();
+                }
+            }
+        }
+        html.push_str("</pre>");
+        html
+    }
+
+    /// Exports the screen as an SVG image
+    ///
+    /// Each cell is 10 pixels wide and 20 pixels high
+    pub fn to_svg(&self) -> String {
+        let width = self.get_width() * CELL_WIDTH;
+        let height = self.get_height() * CELL_HEIGHT;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\" font-family=\"monospace\" font-size=\"{2}\">\n",
+            width, height, FONT_SIZE
+        );
+        writeln!(
+            svg,
+            "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+            DEFAULT_BG
+        )
+        .unwrap();
+        for y in 0..self.get_height() {
+            let runs = self.runs(y);
+            // backgrounds first, so they don't hide the text of the previous runs
+            for (run, bg) in runs.iter().filter_map(|run| Some((run, colors(run).1?))) {
+                writeln!(
+                    svg,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    run.x * CELL_WIDTH,
+                    y * CELL_HEIGHT,
+                    run.cells * CELL_WIDTH,
+                    CELL_HEIGHT,
+                    bg
+                )
+                .unwrap();
+            }
+            for run in runs
+                .iter()
+                .filter(|run| !run.text.trim().is_empty() && !run.style.hidden)
+            {
+                let mut attributes = String::new();
+                if run.style.bold {
+                    attributes.push_str(" font-weight=\"bold\"");
+                }
+                if run.style.italic {
+                    attributes.push_str(" font-style=\"italic\"");
+                }
+                if run.style.dim {
+                    attributes.push_str(" opacity=\"0.5\"");
+                }
+                let decoration = decoration(run.style);
+                if !decoration.is_empty() {
+                    write!(attributes, " text-decoration=\"{}\"", decoration).unwrap();
+                }
+                writeln!(
+                    svg,
+                    "<text x=\"{}\" y=\"{}\" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\" fill=\"{}\"{} xml:space=\"preserve\">{}</text>",
+                    run.x * CELL_WIDTH,
+                    // baseline of the text, leaving room for descenders
+                    y * CELL_HEIGHT + FONT_SIZE - 1,
+                    run.cells * CELL_WIDTH,
+                    colors(run).0.unwrap_or_else(|| String::from(DEFAULT_FG)),
+                    attributes,
+                    escape(&run.text)
+                )
+                .unwrap();
+            }
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Splits a row into runs of cells sharing the same colors and style
+    fn runs(&self, y: u32) -> Vec<Run> {
+        let mut runs: Vec<Run> = vec![];
+        let mut x = 0;
+        while x < self.get_width() {
+            let pxl = self.get_pxl(x as i32, y as i32).unwrap();
+            let cells = if pxl.is_wide() {
+                2.min(self.get_width() - x)
+            } else {
+                1
+            };
+            // empty screens are filled with NUL characters,
+            // and the right half of overwritten wide characters is left alone
+            let grapheme = if pxl.chr.is_control() || pxl.is_continuation() {
+                String::from(" ")
+            } else {
+                self.get_grapheme(x as i32, y as i32).unwrap()
+            };
+            match runs.last_mut() {
+                Some(run) if (run.fg, run.bg, run.style) == (pxl.fg, pxl.bg, pxl.style) => {
+                    run.text.push_str(&grapheme);
+                    run.cells += cells;
+                }
+                _ => runs.push(Run {
+                    x,
+                    cells,
+                    text: grapheme,
+                    fg: pxl.fg,
+                    bg: pxl.bg,
+                    style: pxl.style,
+                }),
+            }
+            x += cells;
+        }
+        runs
+    }
+}
+
+/// Foreground and background colors of a run, `None` for the default ones
+fn colors(run: &Run) -> (Option<String>, Option<String>) {
+    let fg = (run.fg != Color::Reset).then(|| to_hex(run.fg, DEFAULT_FG));
+    let bg = (run.bg != Color::Reset).then(|| to_hex(run.bg, DEFAULT_BG));
+    if run.style.reverse {
+        (
+            Some(bg.unwrap_or_else(|| String::from(DEFAULT_BG))),
+            Some(fg.unwrap_or_else(|| String::from(DEFAULT_FG))),
+        )
+    } else {
+        (fg, bg)
+    }
+}
+
+/// Inline CSS of a run, empty if it uses the default colors and style
+fn css(run: &Run) -> String {
+    let mut css = vec![];
+    let (fg, bg) = colors(run);
+    if run.style.hidden {
+        css.push(String::from("color: transparent"));
+    } else if let Some(fg) = fg {
+        css.push(format!("color: {}", fg));
+    }
+    if let Some(bg) = bg {
+        css.push(format!("background-color: {}", bg));
+    }
+    if run.style.bold {
+        css.push(String::from("font-weight: bold"));
+    }
+    if run.style.dim {
+        css.push(String::from("opacity: 0.5"));
+    }
+    if run.style.italic {
+        css.push(String::from("font-style: italic"));
+    }
+    let decoration = decoration(run.style);
+    if !decoration.is_empty() {
+        css.push(format!("text-decoration: {}", decoration));
+    }
+    css.join("; ")
+}
+
+/// Value of the text-decoration property for a style
+fn decoration(style: Style) -> String {
+    let mut decoration = vec![];
+    if style.underlined {
+        decoration.push(String::from("underline"));
+    }
+    if style.strikethrough {
+        decoration.push(String::from("line-through"));
+    }
+    // the underline style and color also apply to the strikethrough line
+    if style.underlined {
+        match style.underline_style {
+            UnderlineStyle::Single => {}
+            UnderlineStyle::Double => decoration.push(String::from("double")),
+            UnderlineStyle::Curly => decoration.push(String::from("wavy")),
+            UnderlineStyle::Dotted => decoration.push(String::from("dotted")),
+        }
+        if let Some(color) = style.underline_color {
+            decoration.push(to_hex(color, DEFAULT_FG));
+        }
+    }
+    decoration.join(" ")
+}
+
+fn to_hex(color: Color, default: &str) -> String {
+    match color::to_rgb(color) {
+        Some((r, g, b)) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        None => String::from(default),
+    }
+}
+
+/// Escapes the characters having a meaning in both HTML and XML
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for chr in text.chars() {
+        match chr {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(chr),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::{self, Style, UnderlineStyle};
+    use crate::screen::Screen;
+    use crate::Color;
+
+    fn sample() -> Screen {
+        let mut scr = Screen::new(6, 2);
+        scr.print(0, 0, "a<b");
+        scr.set_pxl(
+            3,
+            0,
+            pixel::pxl(
+                'X',
+                Some(Color::Red),
+                Some(Color::Rgb { r: 1, g: 2, b: 3 }),
+                Some(Style {
+                    bold: true,
+                    underlined: true,
+                    strikethrough: true,
+                    ..Style::default()
+                }),
+            ),
+        );
+        scr.set_pxl(0, 1, pixel::pxl_plain('界'));
+        scr.set_pxl(2, 1, pixel::pxl_plain('x'));
+        scr
+    }
+
+    #[test]
+    fn extended_styles() {
+        let mut scr = Screen::new(3, 1);
+        let style = |style| pixel::pxl('x', Some(Color::Red), None, Some(style));
+        scr.set_pxl(
+            0,
+            0,
+            style(Style {
+                reverse: true,
+                dim: true,
+                ..Style::default()
+            }),
+        );
+        scr.set_pxl(
+            1,
+            0,
+            style(Style {
+                hidden: true,
+                ..Style::default()
+            }),
+        );
+        scr.set_pxl(
+            2,
+            0,
+            style(Style {
+                underlined: true,
+                underline_style: UnderlineStyle::Curly,
+                underline_color: Some(Color::Blue),
+                ..Style::default()
+            }),
+        );
+        assert_eq!(
+            scr.to_html(),
+            concat!(
+                "<pre style=\"font-family: monospace; color: #e5e5e5; background-color: #000000;\">",
+                "<span style=\"color: #000000; background-color: #ff0000; opacity: 0.5\">x</span>",
+                "<span style=\"color: transparent\">x</span>",
+                "<span style=\"color: #ff0000; text-decoration: underline wavy #5c5cff\">x</span>",
+                "</pre>"
+            )
+        );
+
+        let svg = scr.to_svg();
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"10\" height=\"20\" fill=\"#ff0000\"/>"));
+        assert!(svg.contains("fill=\"#000000\" opacity=\"0.5\" xml:space=\"preserve\">x</text>"));
+        // hidden text isn't written
+        assert!(!svg.contains("x=\"10\""));
+    }
+
+    #[test]
+    fn html() {
+        assert_eq!(
+            sample().to_html(),
+            concat!(
+                "<pre style=\"font-family: monospace; color: #e5e5e5; background-color: #000000;\">",
+                "a&lt;b",
+                "<span style=\"color: #ff0000; background-color: #010203; font-weight: bold; text-decoration: underline line-through\">X</span>",
+                "  \n",
+                "界x   </pre>"
+            )
+        );
+    }
+
+    #[test]
+    fn svg() {
+        let svg = sample().to_svg();
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"60\" height=\"40\"")
+        );
+        assert!(svg.ends_with("</svg>"));
+        assert!(
+            svg.contains("<rect x=\"30\" y=\"0\" width=\"10\" height=\"20\" fill=\"#010203\"/>")
+        );
+        assert!(svg.contains(
+            "<text x=\"30\" y=\"15\" textLength=\"10\" lengthAdjust=\"spacingAndGlyphs\" fill=\"#ff0000\" font-weight=\"bold\" text-decoration=\"underline line-through\" xml:space=\"preserve\">X</text>"
+        ));
+        // the wide character covers two cells
+        assert!(svg.contains(
+            "<text x=\"0\" y=\"35\" textLength=\"60\" lengthAdjust=\"spacingAndGlyphs\" fill=\"#e5e5e5\" xml:space=\"preserve\">界x   </text>"
+        ));
+        assert!(svg.contains(">a&lt;b</text>"));
+    }
+}
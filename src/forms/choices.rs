@@ -98,7 +98,7 @@ impl FormField for Radio {
         if let Event::Key(KeyEvent {
             code,
             modifiers: _,
-            kind: KeyEventKind::Press,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
             state: _,
         }) = event
         {
@@ -270,7 +270,7 @@ impl FormField for Checkbox {
         if let Event::Key(KeyEvent {
             code,
             modifiers: _,
-            kind: KeyEventKind::Press,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
             state: _,
         }) = event
         {
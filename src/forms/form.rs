@@ -150,6 +150,16 @@ impl Form {
         }
     }
 
+    /// Gives an event to the currently active field
+    fn forward_event(&mut self, event: Event) {
+        for (_, field) in self.fields.iter_mut() {
+            if field.is_active() {
+                field.handle_event(event);
+                break;
+            }
+        }
+    }
+
     /// Checks whenever the user went through the entire form, and confirmed on the last field
     pub fn is_finished(&self) -> bool {
         self.index >= self.fields.len()
@@ -208,10 +218,15 @@ impl FormField for Form {
         if !self.active {
             return;
         }
+        // pasted text goes to the active field, even if it contains newlines
+        if let Event::Paste(_) = event {
+            self.forward_event(event);
+            return;
+        }
         if let Event::Key(KeyEvent {
             code,
             modifiers: _,
-            kind: KeyEventKind::Press,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
             state: _,
         }) = event
         {
@@ -235,18 +250,17 @@ impl FormField for Form {
                 KeyCode::PageUp => {
                     self.scroll(-1);
                 }
-                _ => {
-                    for (_, field) in self.fields.iter_mut() {
-                        if field.is_active() {
-                            field.handle_event(event);
-                            break;
-                        }
-                    }
-                }
+                _ => self.forward_event(event),
             }
         }
     }
 
+    fn take_copied(&mut self) -> Option<String> {
+        self.fields
+            .iter_mut()
+            .find_map(|(_, field)| field.take_copied())
+    }
+
     fn set_active(&mut self, active: bool) {
         self.active = active;
         self.update_active_field();
@@ -42,6 +42,14 @@ pub trait FormField {
     /// This function is the heart of FormFields : it allows the form to handle itself by passing a ConsoleEngine Event to it.
     fn handle_event(&mut self, event: Event);
 
+    /// Takes the text the user asked to copy to the clipboard since the last call (e.g. with Ctrl+C), if any
+    ///
+    /// Fields can't reach the terminal, so the application copies it with [ConsoleEngine::set_clipboard](crate::ConsoleEngine::set_clipboard).
+    /// Fields that don't support copying return `None`.
+    fn take_copied(&mut self) -> Option<String> {
+        None
+    }
+
     /// Set the active state of a field (if applicable)
     fn set_active(&mut self, active: bool);
     /// Checks if the state of a field is active
@@ -88,8 +96,9 @@ pub trait FormField {
 pub type FormValidationResult = Vec<String>;
 
 /// Type that stores a potential output coming from a Form Field
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum FormValue {
+    #[default]
     Nothing,
     Boolean(bool),
     Index(usize),
@@ -99,12 +108,6 @@ pub enum FormValue {
     Map(HashMap<String, FormValue>),
 }
 
-impl Default for FormValue {
-    fn default() -> Self {
-        Self::Nothing
-    }
-}
-
 #[derive(Debug, Clone)]
 pub enum FormError {
     FieldNotFound,
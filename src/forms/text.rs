@@ -4,11 +4,31 @@ use crate::{events::Event, pixel, screen::Screen};
 
 use super::{FormField, FormOptions, FormValidationResult, FormValue};
 
+/// Inserts a string in `buffer` before the character at `cursor_pos`, leaving out control characters
+///
+/// Returns the number of inserted characters
+fn insert_str(buffer: &mut String, cursor_pos: usize, string: &str) -> usize {
+    let inserted = string.chars().filter(|chr| !chr.is_control());
+    let mut new_buffer = String::with_capacity(buffer.capacity() + string.len());
+    new_buffer.extend(
+        buffer
+            .chars()
+            .take(cursor_pos)
+            .chain(inserted.clone())
+            .chain(buffer.chars().skip(cursor_pos)),
+    );
+    *buffer = new_buffer;
+    inserted.count()
+}
+
 /// Generic text input
 ///
 /// This form field generates a generic text input, that handles keyboard input (moving cursor, backspacing / deleting, home / end)
 /// This field is inactive by default, you need to set it active once created
 ///
+/// Ctrl+C copies the value of the field and Ctrl+X cuts it, the application gives it to the clipboard,
+/// see [take_copied](FormField::take_copied). Pasted text is inserted at the position of the cursor.
+///
 /// Outputs `FormValue::String`
 ///
 /// see example `form-input` for basic usage
@@ -19,6 +39,7 @@ pub struct Text {
     input_buffer: String,
     cursor_pos: usize,
     options: FormOptions,
+    copied: Option<String>,
 }
 
 impl Text {
@@ -30,6 +51,7 @@ impl Text {
             input_buffer: String::new(),
             cursor_pos: 0,
             options,
+            copied: None,
         }
     }
 
@@ -40,6 +62,11 @@ impl Text {
         self.move_cursor(i32::MAX);
     }
 
+    /// Get the value of the field, e.g. to copy it with [ConsoleEngine::set_clipboard](crate::ConsoleEngine::set_clipboard)
+    pub fn get_input_buffer(&self) -> &str {
+        &self.input_buffer
+    }
+
     /// Clear the field
     pub fn clear_input_buffer(&mut self) {
         self.dirty = true;
@@ -61,6 +88,14 @@ impl Text {
         self.move_cursor(1);
     }
 
+    /// Insert a string at the position of the cursor, e.g. when pasting
+    ///
+    /// Control characters such as newlines are left out
+    pub fn put_str(&mut self, string: &str) {
+        let count = insert_str(&mut self.input_buffer, self.cursor_pos, string);
+        self.move_cursor(count as i32);
+    }
+
     /// Removes a certain amount of characters either on the left (positive) or right (negative) side of the cursor
     pub fn remove_char(&mut self, amount: i32) {
         if amount == 0 {
@@ -118,10 +153,13 @@ impl FormField for Text {
         if !self.active {
             return;
         }
+        if let Event::Paste(text) = &event {
+            self.put_str(text);
+        }
         if let Event::Key(KeyEvent {
             code,
             modifiers,
-            kind: KeyEventKind::Press,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
             state: _,
         }) = event
         {
@@ -132,6 +170,13 @@ impl FormField for Text {
                 KeyCode::Right => self.move_cursor(1),
                 KeyCode::Home => self.move_cursor(i32::MIN),
                 KeyCode::End => self.move_cursor(i32::MAX),
+                KeyCode::Char('c') if modifiers == KeyModifiers::CONTROL => {
+                    self.copied = Some(self.input_buffer.clone());
+                }
+                KeyCode::Char('x') if modifiers == KeyModifiers::CONTROL => {
+                    self.copied = Some(self.input_buffer.clone());
+                    self.clear_input_buffer();
+                }
                 KeyCode::Char(c) => {
                     if modifiers.is_empty()
                         || modifiers == KeyModifiers::CONTROL | KeyModifiers::ALT
@@ -152,6 +197,10 @@ impl FormField for Text {
         }
     }
 
+    fn take_copied(&mut self) -> Option<String> {
+        self.copied.take()
+    }
+
     fn set_active(&mut self, active: bool) {
         self.active = active;
     }
@@ -218,6 +267,8 @@ impl FormField for Text {
 /// This form field generates a generic text input, that'll hide what the user writes in it. (e.g. for passwords)
 /// This field is inactive by default, you need to set it active once created
 ///
+/// Text can be pasted into it, but its value can't be copied or cut so it never ends up in the clipboard.
+///
 /// Outputs `FormValue::String`
 ///
 /// see example `form-input` for basic usage
@@ -270,6 +321,14 @@ impl HiddenText {
         self.move_cursor(1);
     }
 
+    /// Insert a string at the position of the cursor, e.g. when pasting
+    ///
+    /// Control characters such as newlines are left out
+    pub fn put_str(&mut self, string: &str) {
+        let count = insert_str(&mut self.input_buffer, self.cursor_pos, string);
+        self.move_cursor(count as i32);
+    }
+
     /// Removes a certain amount of characters either on the left (positive) or right (negative) side of the cursor
     pub fn remove_char(&mut self, amount: i32) {
         if amount == 0 {
@@ -324,10 +383,13 @@ impl FormField for HiddenText {
         if !self.active {
             return;
         }
+        if let Event::Paste(text) = &event {
+            self.put_str(text);
+        }
         if let Event::Key(KeyEvent {
             code,
             modifiers,
-            kind: KeyEventKind::Press,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
             state: _,
         }) = event
         {
@@ -424,3 +486,63 @@ impl FormField for HiddenText {
         &self.screen
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{HiddenText, Text};
+    use crate::events::Event;
+    use crate::forms::{Form, FormField, FormOptions, FormValue};
+
+    #[test]
+    fn paste() {
+        let mut text = Text::new(10, FormOptions::default());
+        text.set_active(true);
+        text.set_input_buffer("ad");
+        text.move_cursor(-1);
+        text.handle_event(Event::Paste(String::from("b\nc")));
+        assert_eq!(text.get_input_buffer(), "abcd");
+        text.handle_event(Event::Paste(String::from("e")));
+        assert!(matches!(text.get_output(), FormValue::String(value) if value == "abced"));
+
+        let mut hidden = HiddenText::new(10, '*', FormOptions::default());
+        hidden.set_active(true);
+        hidden.handle_event(Event::Paste(String::from("se\ncret")));
+        assert!(matches!(hidden.get_output(), FormValue::String(value) if value == "secret"));
+    }
+
+    #[test]
+    fn copy_and_cut() {
+        let ctrl = |chr| Event::Key(KeyEvent::new(KeyCode::Char(chr), KeyModifiers::CONTROL));
+        let mut form = Form::new(12, 4, FormOptions::default());
+        form.build_field::<Text>("text", FormOptions::default());
+        form.build_field::<HiddenText>("password", FormOptions::default());
+        form.set_active(true);
+        assert_eq!(form.take_copied(), None);
+
+        form.handle_event(Event::Paste(String::from("value")));
+        form.handle_event(ctrl('c'));
+        assert_eq!(form.take_copied().as_deref(), Some("value"));
+        assert_eq!(form.take_copied(), None);
+        assert!(
+            matches!(form.get_field_output("text"), Some(FormValue::String(value)) if value == "value")
+        );
+
+        form.handle_event(ctrl('x'));
+        assert_eq!(form.take_copied().as_deref(), Some("value"));
+        assert!(
+            matches!(form.get_field_output("text"), Some(FormValue::String(value)) if value.is_empty())
+        );
+
+        // passwords stay out of the clipboard
+        form.handle_event(Event::Key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE)));
+        form.handle_event(Event::Paste(String::from("secret")));
+        form.handle_event(ctrl('c'));
+        form.handle_event(ctrl('x'));
+        assert_eq!(form.take_copied(), None);
+        assert!(
+            matches!(form.get_field_output("password"), Some(FormValue::String(value)) if value == "secret")
+        );
+    }
+}
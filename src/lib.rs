@@ -6,9 +6,18 @@
 
 pub extern crate crossterm;
 
+pub mod actions;
+pub mod backend;
+pub mod builder;
+pub mod ansi;
+pub mod color;
+pub mod export;
+pub mod mouse;
 pub mod pixel;
+pub mod record;
 pub mod rect_style;
 pub mod screen;
+pub mod snapshot;
 pub mod window_manager;
 mod utils;
 
@@ -19,16 +28,22 @@ pub mod events;
 pub mod forms;
 
 use std::io::Error as ErrorKind;
+use actions::{ActionMap, Binding, SequenceTracker};
+use backend::{
+    Backend, CrosstermBackend, CursorShape, HeadlessBackend, HeadlessHandle, KeyboardMode,
+    TerminalSetup,
+};
+use builder::{EngineBuilder, SizePolicy};
+use color::ColorSupport;
 pub use crossterm::event::{KeyCode, KeyEventKind, KeyModifiers, MouseButton};
 pub use crossterm::style::Color;
-use crossterm::terminal::{self, ClearType};
-use crossterm::event::{self, Event, KeyEvent, MouseEvent, MouseEventKind};
-use crossterm::{execute, queue, style};
-use pixel::Pixel;
+use crossterm::event::{Event, KeyEvent, MouseEvent, MouseEventKind};
+use crossterm::style;
+use mouse::{MouseDrag, MouseTracker};
+use pixel::{Pixel, Style};
+use record::{Recording, Replay};
 use rect_style::BorderStyle;
 use screen::Screen;
-use std::io::Write;
-use std::io::{stdout, Stdout};
 
 pub enum ScrollDirection {
     UP,
@@ -47,6 +62,8 @@ pub enum ScrollDirection {
 /// - Terminal handling with a [target frame per seconds](#method.init)
 /// - [Keyboard](#method.is_key_pressed) and [mouse](#method.get_mouse_press) support
 /// - [Terminal resizing](#method.check_resize) support
+/// - [Custom output backends](#method.init_with_backend)
+/// - [Automatic color downsampling](#method.get_color_support) for terminals with limited colors
 ///
 /// # Basic Usage:
 ///
@@ -83,7 +100,8 @@ pub enum ScrollDirection {
 ///
 #[allow(clippy::needless_doctest_main)]
 pub struct ConsoleEngine {
-    stdout: Stdout,
+    backend: Box<dyn Backend>,
+    setup: TerminalSetup,
     time_limit: std::time::Duration,
     /// The current frame count, publicly accessible
     /// Has no purpose internally, use it as you want
@@ -98,42 +116,101 @@ pub struct ConsoleEngine {
     keys_released: Vec<KeyEvent>,
     mouse_events: Vec<MouseEvent>,
     resize_events: Vec<(u16, u16)>,
+    cursor: Option<(u32, u32)>,
+    cursor_shape: CursorShape,
+    /// whether the cursor has been shown after the last frame
+    cursor_shown: bool,
+    clipboard: Option<String>,
+    paste: Option<String>,
+    focused: bool,
+    actions: ActionMap,
+    sequences: SequenceTracker,
+    sequence_timeout: std::time::Duration,
+    recording: Option<Recording>,
+    replay: Option<Replay>,
+    mouse: MouseTracker,
+    click_interval: std::time::Duration,
+    click_tolerance: u32,
 }
 
+// the engine can be moved to another thread
+const _: fn() = || {
+    fn assert_send<T: Send>() {}
+    assert_send::<ConsoleEngine>();
+};
+
 impl ConsoleEngine {
     /// Initialize a screen of the provided width and height, and load the target FPS
+    ///
+    /// For more control over the terminal setup, see [builder](#method.builder)
     pub fn init(width: u32, height: u32, target_fps: u32) -> Result<ConsoleEngine, ErrorKind> {
-        assert!(target_fps > 0, "Target FPS needs to be greater than zero.");
-        let mut engine = ConsoleEngine {
-            stdout: stdout(),
-            time_limit: std::time::Duration::from_millis(1000 / target_fps as u64),
-            frame_count: 0,
-            width,
-            height,
-            screen: Screen::new(width, height),
-            screen_last_frame: Screen::new_empty(width, height),
-            instant: std::time::Instant::now(),
-            keys_pressed: vec![],
-            keys_held: vec![],
-            keys_released: vec![],
-            mouse_events: vec![],
-            resize_events: vec![],
-        };
-        let previous_panic_hook = std::panic::take_hook();
-        std::panic::set_hook(Box::new(move |panic_info| {
-            Self::handle_panic(panic_info);
-            previous_panic_hook(panic_info);
-            std::process::exit(1);
-        }));
-        engine.begin()?;
-        engine.try_resize(width, height)?;
-        Ok(engine)
+        ConsoleEngine::builder()
+            .size(SizePolicy::Fixed(width, height))
+            .target_fps(target_fps)
+            .build()
+    }
+
+    /// Initialize a screen of the provided width and height on a custom [Backend](backend/trait.Backend.html), and load the target FPS
+    ///
+    /// Unlike [init](#method.init), no panic hook is installed since the engine doesn't know how to restore your output.
+    ///
+    /// usage:
+    /// ```no_run
+    /// use console_engine::backend::CrosstermBackend;
+    ///
+    /// let mut engine = console_engine::ConsoleEngine::init_with_backend(CrosstermBackend::new(), 20, 10, 30).unwrap();
+    /// ```
+    pub fn init_with_backend<B: Backend + 'static>(
+        backend: B,
+        width: u32,
+        height: u32,
+        target_fps: u32,
+    ) -> Result<ConsoleEngine, ErrorKind> {
+        ConsoleEngine::builder()
+            .backend(backend)
+            .size(SizePolicy::Fixed(width, height))
+            .target_fps(target_fps)
+            .build()
+    }
+
+    /// Initialize a screen of the provided width and height without any terminal
+    ///
+    /// Returns the engine along with a [HeadlessHandle](backend/struct.HeadlessHandle.html)
+    /// to inject events and inspect what has been drawn.
+    /// Frames don't wait for the target FPS, which makes this mode suitable for tests.
+    /// Colors are drawn as is, regardless of the terminal running the tests.
+    ///
+    /// usage:
+    /// ```
+    /// use console_engine::KeyCode;
+    ///
+    /// let (mut engine, input) = console_engine::ConsoleEngine::init_headless(20, 10, 30).unwrap();
+    /// input.press_key(KeyCode::Char('q'));
+    /// engine.wait_frame();
+    /// assert!(engine.is_key_pressed(KeyCode::Char('q')));
+    /// ```
+    pub fn init_headless(
+        width: u32,
+        height: u32,
+        target_fps: u32,
+    ) -> Result<(ConsoleEngine, HeadlessHandle), ErrorKind> {
+        let backend = HeadlessBackend::new(width as u16, height as u16);
+        let handle = backend.handle();
+        let engine = ConsoleEngine::builder()
+            .backend(backend)
+            .size(SizePolicy::Fixed(width, height))
+            .target_fps(target_fps)
+            .color_support(ColorSupport::TrueColor)
+            .build()?;
+        Ok((engine, handle))
     }
 
     /// Initialize a screen filling the entire terminal with the target FPS
     pub fn init_fill(target_fps: u32) -> Result<ConsoleEngine, ErrorKind> {
-        let size = crossterm::terminal::size()?;
-        ConsoleEngine::init(size.0 as u32, size.1 as u32, target_fps)
+        ConsoleEngine::builder()
+            .size(SizePolicy::Fill)
+            .target_fps(target_fps)
+            .build()
     }
 
     /// Initialize a screen filling the entire terminal with the target FPS
@@ -143,31 +220,103 @@ impl ConsoleEngine {
         height: u32,
         target_fps: u32,
     ) -> Result<ConsoleEngine, ErrorKind> {
-        let mut engine = ConsoleEngine::init_fill(target_fps)?;
-        engine.try_resize(width, height)?;
+        ConsoleEngine::builder()
+            .size(SizePolicy::FillRequire(width, height))
+            .target_fps(target_fps)
+            .build()
+    }
+
+    /// Creates an [EngineBuilder](builder/struct.EngineBuilder.html) to configure how the engine sets up the terminal
+    ///
+    /// usage:
+    /// ```no_run
+    /// use console_engine::builder::SizePolicy;
+    ///
+    /// let mut engine = console_engine::ConsoleEngine::builder()
+    ///     .size(SizePolicy::Fixed(40, 10))
+    ///     .target_fps(60)
+    ///     .mouse_capture(false)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder() -> EngineBuilder {
+        EngineBuilder::new()
+    }
+
+    /// Starts the engine on the given backend, called by [EngineBuilder::build](builder/struct.EngineBuilder.html#method.build)
+    fn start(
+        backend: Box<dyn Backend>,
+        setup: TerminalSetup,
+        size: SizePolicy,
+        target_fps: u32,
+    ) -> Result<ConsoleEngine, ErrorKind> {
+        let mut engine = ConsoleEngine {
+            backend,
+            setup: TerminalSetup {
+                // detected once, every draw downsamples with the stored value
+                color_support: setup.color_support.resolve(),
+                ..setup
+            },
+            time_limit: std::time::Duration::from_millis(1000 / target_fps as u64),
+            frame_count: 0,
+            width: 0,
+            height: 0,
+            screen: Screen::new(0, 0),
+            screen_last_frame: Screen::new_empty(0, 0),
+            instant: std::time::Instant::now(),
+            keys_pressed: vec![],
+            keys_held: vec![],
+            keys_released: vec![],
+            mouse_events: vec![],
+            resize_events: vec![],
+            cursor: None,
+            cursor_shape: CursorShape::default(),
+            cursor_shown: false,
+            clipboard: None,
+            paste: None,
+            focused: true,
+            actions: ActionMap::new(),
+            sequences: SequenceTracker::default(),
+            sequence_timeout: std::time::Duration::from_secs(1),
+            recording: None,
+            replay: None,
+            mouse: MouseTracker::default(),
+            click_interval: std::time::Duration::from_millis(500),
+            click_tolerance: 1,
+        };
+        engine.begin()?;
+        match size {
+            SizePolicy::Fixed(width, height) => {
+                engine.resize(width, height);
+                engine.try_resize(width, height)?;
+            }
+            SizePolicy::Fill => {
+                let (width, height) = engine.backend.size()?;
+                engine.resize(width as u32, height as u32);
+            }
+            SizePolicy::FillRequire(width, height) => {
+                let size = engine.backend.size()?;
+                engine.resize(size.0 as u32, size.1 as u32);
+                engine.try_resize(width, height)?;
+            }
+        }
         Ok(engine)
     }
 
     /// Try to resize the terminal to match the asked width and height at minimum
     fn try_resize(&mut self, width: u32, height: u32) -> Result<(), ErrorKind> {
-        let size = crossterm::terminal::size()?;
+        let size = self.backend.size()?;
         if (size.0 as u32) < width || (size.1 as u32) < height {
-            execute!(
-                self.stdout,
-                crossterm::terminal::SetSize(width as u16, height as u16),
-                crossterm::terminal::SetSize(width as u16, height as u16)
-            )?;
+            self.backend.set_size(width as u16, height as u16)?;
             self.resize(width, height);
             // flush events
             #[cfg(feature = "event")]
             use std::time::Duration;
             #[cfg(feature = "event")]
-            while let Ok(true) = event::poll(Duration::from_micros(100)) {
-                event::read().ok();
-            }
+            while let Ok(Some(_)) = self.backend.poll_event(Duration::from_micros(100)) {}
         }
-        if crossterm::terminal::size()? < (width as u16, height as u16) {
-            Err(ErrorKind::new(std::io::ErrorKind::Other, format!("Your terminal must have at least a width and height of {}x{} characters. Currently has {}x{}", width, height, size.0, size.1)))
+        if self.backend.size()? < (width as u16, height as u16) {
+            Err(ErrorKind::other(format!("Your terminal must have at least a width and height of {}x{} characters. Currently has {}x{}", width, height, size.0, size.1)))
         } else {
             Ok(())
         }
@@ -175,50 +324,56 @@ impl ConsoleEngine {
 
     /// Initializes the internal components such as hiding the cursor
     fn begin(&mut self) -> Result<(), ErrorKind> {
-        terminal::enable_raw_mode().unwrap();
-        execute!(
-            self.stdout,
-            terminal::EnterAlternateScreen,
-            terminal::Clear(ClearType::All),
-            crossterm::cursor::Hide,
-            crossterm::cursor::MoveTo(0, 0),
-            crossterm::event::EnableMouseCapture
-        )
+        self.backend.begin(&self.setup)
     }
 
     /// Gracefully stop the engine, and set back a visible cursor
-    fn end(&mut self) {
-        execute!(
-            self.stdout,
-            crossterm::cursor::Show,
-            style::SetBackgroundColor(Color::Reset),
-            style::SetForegroundColor(Color::Reset),
-            crossterm::event::DisableMouseCapture,
-            terminal::LeaveAlternateScreen
-        )
-        .unwrap();
-        terminal::disable_raw_mode().unwrap();
+    fn end(&mut self) -> Result<(), ErrorKind> {
+        self.backend.end(&self.setup)
     }
 
     /// stops the engine when a panic occurs
     /// Similar to the end function, but without the engine instance.
     /// So we assume we used stdout, and free it.
-    fn handle_panic(_panic_info: &std::panic::PanicInfo) {
-        execute!(
-            stdout(),
-            crossterm::cursor::Show,
-            style::SetBackgroundColor(Color::Reset),
-            style::SetForegroundColor(Color::Reset),
-            crossterm::event::DisableMouseCapture,
-            terminal::LeaveAlternateScreen
-        )
-        .unwrap();
-        terminal::disable_raw_mode().unwrap();
+    ///
+    /// Errors are ignored since we can't do anything about them while panicking
+    fn handle_panic(_panic_info: &std::panic::PanicHookInfo, setup: &TerminalSetup) {
+        CrosstermBackend::restore(setup).ok();
     }
 
     /// Set the terminal's title
     pub fn set_title(&mut self, title: &str) {
-        execute!(self.stdout, crossterm::terminal::SetTitle(title)).ok();
+        self.backend.set_title(title).ok();
+    }
+
+    /// Copies some text into the system clipboard, through the terminal (OSC 52)
+    ///
+    /// This works over SSH, as long as the terminal supports it. Other terminals ignore it.
+    pub fn set_clipboard(&mut self, content: &str) {
+        self.backend.set_clipboard(content).ok();
+    }
+
+    /// Asks the terminal for the content of the system clipboard, see [get_clipboard](#method.get_clipboard)
+    ///
+    /// Many terminals refuse to share the clipboard, or ask the user first, so the answer may never come.
+    /// The answer is awaited for a few seconds, key presses are handled as usual afterwards.
+    ///
+    /// usage:
+    /// ```
+    /// if engine.is_key_pressed_with_modifier(KeyCode::Char('v'), KeyModifiers::CONTROL) {
+    ///     engine.request_clipboard();
+    /// }
+    /// if let Some(content) = engine.get_clipboard() {
+    ///     text_field.put_str(content);
+    /// }
+    /// ```
+    pub fn request_clipboard(&mut self) {
+        self.backend.request_clipboard().ok();
+    }
+
+    /// Get the content of the clipboard received during the last frame, after a [request_clipboard](#method.request_clipboard)
+    pub fn get_clipboard(&self) -> Option<&str> {
+        self.clipboard.as_deref()
     }
 
     /// Get the screen width
@@ -267,6 +422,34 @@ impl ConsoleEngine {
         self.screen.print_fbg(x, y, string, fg, bg)
     }
 
+    /// prints a clickable hyperlink at the specified coordinates, displaying the given text
+    ///
+    /// Terminals supporting OSC 8 hyperlinks open the target when the text is clicked,
+    /// other terminals display the text as usual.
+    ///
+    /// usage:
+    /// ```
+    /// engine.print_link(0, 0, "documentation", "https://docs.rs/console_engine");
+    /// ```
+    pub fn print_link(&mut self, x: i32, y: i32, string: &str, link: &str) {
+        self.screen.print_link(x, y, string, link)
+    }
+
+    /// prints a clickable hyperlink at the specified coordinates with the specified foreground and background color
+    ///
+    /// see [print_link](#method.print_link)
+    pub fn print_link_fbg(
+        &mut self,
+        x: i32,
+        y: i32,
+        string: &str,
+        link: &str,
+        fg: Color,
+        bg: Color,
+    ) {
+        self.screen.print_link_fbg(x, y, string, link, fg, bg)
+    }
+
     /// Prints another screen on specified coordinates.
     /// Useful when you want to manage several "subscreen"
     ///
@@ -485,6 +668,26 @@ impl ConsoleEngine {
         self.screen.get_pxl(x, y)
     }
 
+    /// sets a grapheme in the specified coordinates, with the colors and style of the given Pixel
+    /// out of bounds pixels will be ignored
+    ///
+    /// see [Screen::set_grapheme](screen/struct.Screen.html#method.set_grapheme)
+    ///
+    /// usage:
+    /// ```
+    /// use console_engine::pixel;
+    /// // ...
+    /// engine.set_grapheme(0, 0, "🇫🇷", pixel::pxl_plain(' '));
+    /// ```
+    pub fn set_grapheme(&mut self, x: i32, y: i32, grapheme: &str, pixel: Pixel) {
+        self.screen.set_grapheme(x, y, grapheme, pixel)
+    }
+
+    /// Get the grapheme displayed at provided coordinates, be it a single character or a grapheme cluster
+    pub fn get_grapheme(&self, x: i32, y: i32) -> Result<String, String> {
+        self.screen.get_grapheme(x, y)
+    }
+
     /// Resizes the screen to match the given width and height
     /// truncates the bottom and right side of the screen
     ///
@@ -575,19 +778,52 @@ impl ConsoleEngine {
     /// engine.print(0,0,"Hello, world!"); // <- prints "Hello, world!" in 'screen' memory
     /// engine.draw(); // display 'screen' memory to the user's terminal
     /// ```
+    ///
+    /// Panics if the screen can't be written, see [try_draw](#method.try_draw) to handle the error instead
     pub fn draw(&mut self) {
-        // we use the queue! macro to store in one-shot the screen we'll write.
+        self.try_draw().unwrap()
+    }
+
+    /// Draw the screen in the terminal, returning any error that occured while writing it
+    ///
+    /// If an error occurs, the next call will redraw the entire screen.
+    ///
+    /// See [draw](#method.draw) for more info about the drawing process
+    ///
+    /// usage:
+    /// ```
+    /// if let Err(error) = engine.try_draw() {
+    ///     // the terminal is gone, stop the application
+    /// }
+    /// ```
+    pub fn try_draw(&mut self) -> Result<(), ErrorKind> {
+        let result = self.draw_frame();
+        if result.is_err() {
+            // we don't know what has been written, so the next frame must be entirely redrawn
+            self.request_full_draw();
+        }
+        result
+    }
+
+    /// Writes the difference between the current screen and the last frame into the backend
+    fn draw_frame(&mut self) -> Result<(), ErrorKind> {
+        // the backend queues everything we send to it, and only writes the whole screen in one-shot when flushed.
         // This is an optimization because we write all we need once instead of writing small bit of screen by small bit of screen.
         // Actually, this does not change much for Linux terminals (like 5 fps gained from this)
         // But for windows terminal we can see huge improvements (example lines-fps goes from 35-40 fps to 65-70 for a 100x50 term)
+        // hold the display until the whole frame has been written, on terminals supporting it
+        self.backend.begin_synchronized_update()?;
+        // the visible cursor would follow the drawing
+        if self.cursor_shown {
+            self.backend.hide_cursor()?;
+            self.cursor_shown = false;
+        }
         // reset cursor position
-        queue!(self.stdout, crossterm::cursor::MoveTo(0, 0)).unwrap();
+        self.backend.move_cursor(0, 0)?;
         let mut first = true;
         let mut current_colors: (Color, Color) = (Color::Reset, Color::Reset);
-        let mut current_bold: bool = false;
-        let mut current_italic: bool = false;
-        let mut current_underline: bool = false;
-        let mut current_strikethrough: bool = false;
+        let mut current_style = Style::default();
+        let mut current_link = None;
         let mut moving = false;
         self.screen_last_frame.check_empty(); // refresh internal "empty" value of the last_frame screen
         let mut skip_next = false;
@@ -602,19 +838,26 @@ impl ConsoleEngine {
                     skip_next = false;
                     continue;
                 }
-                if let Some(char_width) = unicode_width::UnicodeWidthChar::width(pixel.chr) {
-                    if char_width > 1 {
-                        skip_next = true;
+                // the next cell is covered by the right half of a wide character
+                skip_next = pixel.is_wide();
+                let link = self.screen.link_id(x, y);
+                let changed = match self.screen_last_frame.get_pxl(x, y) {
+                    Ok(last_pixel) => {
+                        pixel != last_pixel
+                            || link != self.screen_last_frame.link_id(x, y)
+                            // the character of a dropped cluster may since refer to another one
+                            || (pixel.is_cluster()
+                                && self.screen.cluster(pixel.chr)
+                                    != self.screen_last_frame.cluster(last_pixel.chr))
                     }
-                }
-                if self.screen_last_frame.is_empty()
-                    || pixel != self.screen_last_frame.get_pxl(x, y).unwrap()
-                {
+                    Err(_) => true,
+                };
+                if self.screen_last_frame.is_empty() || changed {
                     if moving {
                         // if the moving flag is set, we need to write a goto instruction first
                         // this optimization minimize useless write on the screen
                         // actually writing to the screen is very slow so it's a good compromise
-                        queue!(self.stdout, crossterm::cursor::MoveTo(x as u16, y as u16)).unwrap();
+                        self.backend.move_cursor(x as u16, y as u16)?;
                         moving = false;
                     }
                     // we check if the last pixel is the same as the current one.
@@ -622,81 +865,114 @@ impl ConsoleEngine {
                     // the less we write on the output the faster we'll get
                     // and additional characters for colors we already have set is
                     // time consuming
-                    if current_bold != pixel.style.bold || first {
-                        current_bold = pixel.style.bold;
-                        if pixel.style.bold {
-                            queue!(self.stdout, style::SetAttribute(style::Attribute::Bold)).unwrap(); 
-                        } else {
-                            // style::Attribute::NoBold is unreliable across terminals
-                            // Use style::Attribute::Reset instead, process italics and underline
-                            // afterwards to avoid resetting those attributes.
-                            queue!(self.stdout, style::SetAttribute(style::Attribute::Reset)).unwrap();
-                            // Make sure to re-enable italics, underline, and strikethrough after they're reset
-                            if pixel.style.italic {
-                                queue!(self.stdout, style::SetAttribute(style::Attribute::Italic)).unwrap();
-                            }
-                            if pixel.style.underlined {
-                                queue!(self.stdout, style::SetAttribute(style::Attribute::Underlined)).unwrap();
-                            }
-                            if pixel.style.strikethrough {
-                                queue!(self.stdout, style::SetAttribute(style::Attribute::CrossedOut)).unwrap();
-                            }
-                            // Also reset the foreground and background colors
-                            queue!(
-                                self.stdout,
-                                style::SetForegroundColor(pixel.fg),
-                                style::SetBackgroundColor(pixel.bg),
-                            ).unwrap();
-                        }
-                    }
-                    if current_italic != pixel.style.italic || first {
-                        current_italic = pixel.style.italic;
-                        if pixel.style.italic {
-                            queue!(self.stdout, style::SetAttribute(style::Attribute::Italic)).unwrap(); 
-                        } else {
-                            queue!(self.stdout, style::SetAttribute(style::Attribute::NoItalic)).unwrap();
+                    if pixel.style != current_style || first {
+                        if first {
+                            // we don't know which attributes are currently set on the terminal
+                            self.backend.set_attribute(style::Attribute::Reset)?;
+                            current_style = Style::default();
                         }
-                    }
-                    if current_underline != pixel.style.underlined || first {
-                        current_underline = pixel.style.underlined;
-                        if pixel.style.underlined {
-                            queue!(self.stdout, style::SetAttribute(style::Attribute::Underlined)).unwrap(); 
-                        } else {
-                            queue!(self.stdout, style::SetAttribute(style::Attribute::NoUnderline)).unwrap();
+                        for attribute in pixel.style.attributes_from(&current_style) {
+                            self.backend.set_attribute(attribute)?;
                         }
-                    }
-                    if current_strikethrough != pixel.style.strikethrough || first {
-                        current_strikethrough = pixel.style.strikethrough;
-                        if pixel.style.strikethrough {
-                            queue!(self.stdout, style::SetAttribute(style::Attribute::CrossedOut)).unwrap();
-                        } else {
-                            queue!(self.stdout, style::SetAttribute(style::Attribute::NotCrossedOut)).unwrap();
+                        if pixel.style.underline_color != current_style.underline_color {
+                            let color = pixel.style.underline_color.unwrap_or(Color::Reset);
+                            let support = self.setup.color_support;
+                            self.backend.set_underline_color(support.downsample(color))?;
                         }
+                        current_style = pixel.style;
                     }
                     if current_colors != pixel.get_colors() || first {
                         current_colors = pixel.get_colors();
-                        queue!(
-                            self.stdout,
-                            style::SetForegroundColor(pixel.fg),
-                            style::SetBackgroundColor(pixel.bg),
-                        ).unwrap();
+                        self.set_colors(pixel.fg, pixel.bg)?;
+                    }
+                    if link != current_link {
+                        current_link = link;
+                        self.backend.set_link(self.screen.get_link(x, y))?;
                     }
                     first = false;
-                    queue!(self.stdout, style::Print(pixel.chr)).unwrap();
+                    // the right half of a wide character that has been overwritten
+                    let mut buffer = [0; 4];
+                    let grapheme = if pixel.is_continuation() {
+                        " "
+                    } else {
+                        self.screen.encode_grapheme(pixel.chr, &mut buffer)
+                    };
+                    self.backend.print(grapheme)?;
                 } else {
                     moving = true
                 }
             }
+            // a wide character never covers the first cell of the next row
+            skip_next = false;
             // at the end of each line, we write a newline character
             // I believe that since we're on raw mode we need CR and LF even on unix terminals
             if y < self.height as i32 - 1 {
-                queue!(self.stdout, style::Print("\r\n")).unwrap();
+                self.backend.print("\r\n")?;
+            }
+        }
+        // links must not extend to what is printed outside of the engine
+        if current_link.is_some() {
+            self.backend.set_link(None)?;
+        }
+        // place the cursor where the user asked for
+        if let Some((x, y)) = self.cursor {
+            if x < self.width && y < self.height {
+                self.backend
+                    .show_cursor(x as u16, y as u16, self.cursor_shape)?;
+                self.cursor_shown = true;
             }
         }
+        self.backend.end_synchronized_update()?;
         // flush the buffer into user's terminal
-        self.stdout.flush().unwrap();
+        self.backend.flush()?;
         // store the frame for the next draw call
         self.screen_last_frame = self.screen.clone();
+        Ok(())
+    }
+
+    /// Sends the colors to the backend, mapped to what the terminal is able to display
+    fn set_colors(&mut self, fg: Color, bg: Color) -> Result<(), ErrorKind> {
+        let support = self.setup.color_support;
+        self.backend
+            .set_colors(support.downsample(fg), support.downsample(bg))
+    }
+
+    /// Get the colors supported by the terminal, as detected when the engine started
+    ///
+    /// Colors drawn by the engine are mapped to the nearest supported one
+    pub fn get_color_support(&self) -> ColorSupport {
+        self.setup.color_support
+    }
+
+    /// Shows the terminal cursor at the given position after each `draw` call, or hides it with `None`
+    ///
+    /// Useful to show where the user is typing. The cursor is hidden if the position is outside of the screen.
+    ///
+    /// usage:
+    /// ```
+    /// engine.print(0, 0, "Name: ");
+    /// engine.set_cursor(Some((6, 0)));
+    /// engine.draw();
+    /// ```
+    pub fn set_cursor(&mut self, position: Option<(u32, u32)>) {
+        self.cursor = position;
+    }
+
+    /// Get the position of the terminal cursor, as set by [set_cursor](#method.set_cursor)
+    pub fn get_cursor(&self) -> Option<(u32, u32)> {
+        self.cursor
+    }
+
+    /// Changes the shape of the cursor shown with [set_cursor](#method.set_cursor)
+    ///
+    /// Terminals not supporting cursor shapes keep their default one
+    pub fn set_cursor_shape(&mut self, shape: CursorShape) {
+        self.cursor_shape = shape;
+    }
+
+    /// Get the shape of the cursor shown with [set_cursor](#method.set_cursor)
+    pub fn get_cursor_shape(&self) -> CursorShape {
+        self.cursor_shape
     }
 
     /// Ask the engine to redraw the entire screen on the next `draw` call
@@ -724,48 +1000,163 @@ impl ConsoleEngine {
         let mut captured_keyboard: Vec<KeyEvent> = vec![];
         let mut captured_mouse: Vec<MouseEvent> = vec![];
         let mut captured_resize: Vec<(u16, u16)> = vec![];
+        let mut captured_paste: Option<String> = None;
 
         // if there is time before next frame, poll keyboard and mouse events until next frame
+        // replayed frames end with the recorded frames instead
+        let real_time = self.replay.as_ref().is_some_and(|replay| replay.real_time);
         let mut elapsed_time = self.instant.elapsed();
-        while self.time_limit > elapsed_time {
-            let remaining_time = self.time_limit - elapsed_time;
-            if let Ok(has_event) = event::poll(std::time::Duration::from_millis(
-                (remaining_time.as_millis() % self.time_limit.as_millis()) as u64,
-            )) {
-                if has_event {
-                    if let Ok(current_event) = event::read() {
-                        match current_event {
-                            Event::Key(evt) => {
-                                captured_keyboard.push(evt);
-                            }
-                            Event::Mouse(evt) => {
-                                captured_mouse.push(evt);
-                            }
-                            Event::Resize(w, h) => {
-                                captured_resize.push((w, h));
-                            }
-                            Event::FocusGained => (),
-                            Event::FocusLost => (),
-                            Event::Paste(_) => (),
-                        };
+        while self.replay.is_some() || self.time_limit > elapsed_time {
+            let remaining_time = self.time_limit.saturating_sub(elapsed_time);
+            match self.next_event(remaining_time) {
+                // the backend reached the timeout, the frame is over
+                Ok(None) => break,
+                Ok(Some(current_event)) => match current_event {
+                    Event::Key(evt) => {
+                        captured_keyboard.push(evt);
                     }
-                }
+                    Event::Mouse(evt) => {
+                        captured_mouse.push(evt);
+                    }
+                    Event::Resize(w, h) => {
+                        captured_resize.push((w, h));
+                    }
+                    Event::FocusGained => self.focused = true,
+                    Event::FocusLost => self.focused = false,
+                    Event::Paste(text) => {
+                        captured_paste
+                            .get_or_insert_with(String::new)
+                            .push_str(&text);
+                    }
+                },
+                Err(_) => (),
             }
             elapsed_time = self.instant.elapsed();
         }
-        self.instant = std::time::Instant::now();
-        self.frame_count = self.frame_count.wrapping_add(1);
+        self.end_frame(real_time);
 
-        // updates pressed / held / released states
-        let held = utils::intersect(
-            &utils::union(&self.keys_pressed, &self.keys_held),
-            &captured_keyboard,
+        self.update_keys(&captured_keyboard);
+        self.update_sequences(&captured_keyboard);
+        let interval = self.duration_in_frames(self.click_interval);
+        self.mouse.update(
+            &captured_mouse,
+            self.frame_count,
+            interval,
+            self.click_tolerance,
         );
-        self.keys_released = utils::outersect_left(&self.keys_held, &held);
-        self.keys_pressed = utils::outersect_left(&captured_keyboard, &held);
-        self.keys_held = utils::union(&held, &self.keys_pressed);
         self.mouse_events = captured_mouse;
         self.resize_events = captured_resize;
+        self.paste = captured_paste;
+        self.clipboard = self.backend.take_clipboard();
+    }
+
+    /// reads the next event from the replayed recording if any, from the backend otherwise
+    ///
+    /// `Ok(None)` means that the frame is over
+    fn next_event(&mut self, timeout: std::time::Duration) -> Result<Option<Event>, ErrorKind> {
+        let event = match &mut self.replay {
+            Some(replay) => {
+                let event = replay.next_event();
+                if replay.is_over() {
+                    self.replay = None;
+                }
+                event
+            }
+            None => self.backend.poll_event(timeout)?,
+        };
+        if let (Some(recording), Some(event)) = (&mut self.recording, &event) {
+            recording.push_event(event.clone());
+        }
+        Ok(event)
+    }
+
+    /// starts the next frame, after waiting for the target FPS if the frame was replayed in real time
+    fn end_frame(&mut self, real_time: bool) {
+        if real_time {
+            std::thread::sleep(self.time_limit.saturating_sub(self.instant.elapsed()));
+        }
+        if let Some(recording) = &mut self.recording {
+            recording.push_frame();
+        }
+        self.instant = std::time::Instant::now();
+        self.frame_count = self.frame_count.wrapping_add(1);
+    }
+
+    /// updates pressed / held / released states from the key events captured during the frame
+    fn update_keys(&mut self, captured_keyboard: &[KeyEvent]) {
+        let with_kind =
+            |evt: &KeyEvent, kind| KeyEvent::new_with_kind(evt.code, evt.modifiers, kind);
+        match self.backend.keyboard_mode() {
+            KeyboardMode::Legacy => {
+                // keys are seen as held as long as the terminal keeps repeating them
+                let captured: Vec<KeyEvent> = captured_keyboard
+                    .iter()
+                    .filter(|evt| evt.kind != KeyEventKind::Release)
+                    .map(|evt| with_kind(evt, KeyEventKind::Press))
+                    .collect();
+                let held = utils::intersect(
+                    &utils::union(&self.keys_pressed, &self.keys_held),
+                    &captured,
+                );
+                self.keys_released = utils::outersect_left(&self.keys_held, &held)
+                    .iter()
+                    .map(|evt| with_kind(evt, KeyEventKind::Release))
+                    .collect();
+                self.keys_pressed = utils::outersect_left(&captured, &held);
+                self.keys_held = utils::union(&held, &self.keys_pressed);
+            }
+            KeyboardMode::Enhanced => {
+                self.keys_pressed.clear();
+                self.keys_released.clear();
+                for evt in captured_keyboard {
+                    let key = with_kind(evt, KeyEventKind::Press);
+                    if evt.kind == KeyEventKind::Release {
+                        // modifiers may have changed since the key has been pressed
+                        let mut released: Vec<KeyEvent> = self
+                            .keys_held
+                            .iter()
+                            .filter(|held| utils::same_key(held.code, evt.code))
+                            .map(|held| with_kind(held, KeyEventKind::Release))
+                            .collect();
+                        if released.is_empty() {
+                            released.push(with_kind(evt, KeyEventKind::Release));
+                        }
+                        self.keys_held
+                            .retain(|held| !utils::same_key(held.code, evt.code));
+                        self.keys_released = utils::union(&self.keys_released, &released);
+                    } else if self.keys_held.contains(&key) {
+                        let repeat = [with_kind(evt, KeyEventKind::Repeat)];
+                        self.keys_pressed = utils::union(&self.keys_pressed, &repeat);
+                    } else {
+                        self.keys_pressed.push(key);
+                        self.keys_held.push(key);
+                    }
+                }
+            }
+        }
+    }
+
+    /// feeds the keys pressed during the frame to the sequences of the action map
+    ///
+    /// raw events are used since typing the same key on consecutive frames looks like holding it in legacy mode
+    fn update_sequences(&mut self, captured_keyboard: &[KeyEvent]) {
+        let keys: Vec<KeyEvent> = captured_keyboard
+            .iter()
+            .filter(|key| key.kind == KeyEventKind::Press)
+            .filter(|key| !matches!(key.code, KeyCode::Modifier(_)))
+            .map(|key| KeyEvent::new(key.code, key.modifiers))
+            .collect();
+        let timeout = self.duration_in_frames(self.sequence_timeout);
+        self.sequences
+            .update(&self.actions, &keys, self.frame_count, timeout);
+    }
+
+    /// converts a delay into a number of frames at the target FPS
+    ///
+    /// delays between inputs are counted in frames so that they behave the same when frames are late or replayed
+    fn duration_in_frames(&self, duration: std::time::Duration) -> usize {
+        let frame_time = self.time_limit.as_secs_f64().max(f64::EPSILON);
+        (duration.as_secs_f64() / frame_time).ceil() as usize
     }
 
     /// Poll the next ConsoleEngine Event
@@ -793,6 +1184,7 @@ impl ConsoleEngine {
     pub fn poll(&mut self) -> events::Event {
         use std::time::Duration;
 
+        let real_time = self.replay.as_ref().is_some_and(|replay| replay.real_time);
         let mut elapsed_time = self.instant.elapsed();
         // guarantees that this loop is running at least once
         loop {
@@ -801,29 +1193,49 @@ impl ConsoleEngine {
             } else {
                 Duration::from_millis(0)
             };
-            if let Ok(has_event) = event::poll(std::time::Duration::from_millis(
-                (remaining_time.as_millis() % self.time_limit.as_millis()) as u64,
-            )) {
-                if has_event {
-                    if let Ok(current_event) = event::read() {
-                        match current_event {
-                            Event::Key(evt) => return events::Event::Key(evt),
-                            Event::Mouse(evt) => return events::Event::Mouse(evt),
-                            Event::Resize(w, h) => return events::Event::Resize(w, h),
-                            Event::FocusGained => (),
-                            Event::FocusLost => (),
-                            Event::Paste(_) => (),
-                        };
+            match self.next_event(remaining_time) {
+                // the backend reached the timeout, the frame is over
+                Ok(None) => break,
+                Ok(Some(current_event)) => match current_event {
+                    Event::Key(evt) => return events::Event::Key(evt),
+                    Event::Mouse(evt) => {
+                        let interval = self.duration_in_frames(self.click_interval);
+                        let clicks = self.mouse.handle(
+                            &evt,
+                            self.frame_count,
+                            interval,
+                            self.click_tolerance,
+                        );
+                        return events::Event::Mouse(events::MouseEvent {
+                            kind: evt.kind,
+                            column: evt.column,
+                            row: evt.row,
+                            modifiers: evt.modifiers,
+                            clicks,
+                        });
                     }
-                }
+                    Event::Resize(w, h) => return events::Event::Resize(w, h),
+                    Event::FocusGained => {
+                        self.focused = true;
+                        return events::Event::Focus(true);
+                    }
+                    Event::FocusLost => {
+                        self.focused = false;
+                        return events::Event::Focus(false);
+                    }
+                    Event::Paste(text) => return events::Event::Paste(text),
+                },
+                Err(_) => (),
             }
             elapsed_time = self.instant.elapsed();
-            if self.time_limit <= elapsed_time {
+            // replayed frames end with the recorded frames
+            if self.replay.is_none() && self.time_limit <= elapsed_time {
                 break;
             }
         }
-        self.instant = std::time::Instant::now();
-        self.frame_count = self.frame_count.wrapping_add(1);
+        self.end_frame(real_time);
+        self.mouse.end_frame();
+        self.clipboard = self.backend.take_clipboard();
         events::Event::Frame
     }
 
@@ -841,17 +1253,26 @@ impl ConsoleEngine {
     ///     // do your stuff
     /// }
     /// ```
+    ///
+    /// Panics if the terminal size can't be read, see [try_check_resize](#method.try_check_resize) to handle the error instead
     pub fn check_resize(&mut self) -> bool {
-        if crossterm::terminal::size().unwrap() != (self.width as u16, self.height as u16) {
+        self.try_check_resize().unwrap()
+    }
+
+    /// Check and resize the terminal if needed, returning any error that occured while reading the terminal size
+    ///
+    /// see [check_resize](#method.check_resize) for usage
+    pub fn try_check_resize(&mut self) -> Result<bool, ErrorKind> {
+        let size = self.backend.size()?;
+        if size != (self.width as u16, self.height as u16) {
             // resize terminal
-            let size = crossterm::terminal::size().unwrap();
             let new_width = size.0 as u32;
             let new_height = size.1 as u32;
 
             self.resize(new_width, new_height);
-            return true;
+            Ok(true)
         } else {
-            return false;
+            Ok(false)
         }
     }
 
@@ -1006,6 +1427,202 @@ impl ConsoleEngine {
         None
     }
 
+    /// Sets the actions checked by [is_action_pressed](#method.is_action_pressed) and its siblings
+    ///
+    /// usage:
+    /// ```
+    /// use console_engine::actions::ActionMap;
+    ///
+    /// let config = std::fs::read_to_string("controls.cfg").unwrap();
+    /// engine.set_action_map(ActionMap::load(&config).unwrap());
+    /// ```
+    pub fn set_action_map(&mut self, actions: ActionMap) {
+        self.actions = actions;
+        self.sequences.reset();
+    }
+
+    /// Get the actions checked by the engine
+    pub fn get_action_map(&self) -> &ActionMap {
+        &self.actions
+    }
+
+    /// Get the actions checked by the engine, to rebind them
+    pub fn get_action_map_mut(&mut self) -> &mut ActionMap {
+        self.sequences.reset();
+        &mut self.actions
+    }
+
+    /// Sets the maximum delay between two keys of a sequence (default: 1 second)
+    ///
+    /// The delay is converted into a number of frames at the target FPS,
+    /// see [ActionMap::bind_sequence](actions/struct.ActionMap.html#method.bind_sequence)
+    pub fn set_sequence_timeout(&mut self, timeout: std::time::Duration) {
+        self.sequence_timeout = timeout;
+    }
+
+    /// Give the keys typed so far towards a sequence, to be shown in a status line
+    ///
+    /// Keys are written like in the [action map config](actions/index.html), e.g. `"ctrl+x"`
+    ///
+    /// usage:
+    /// ```
+    /// if let Some(keys) = engine.get_pending_sequence() {
+    ///     engine.print(0, engine.get_height() as i32 - 1, &keys);
+    /// }
+    /// ```
+    pub fn get_pending_sequence(&self) -> Option<String> {
+        self.sequences.get_pending()
+    }
+
+    /// checks whenever one of the inputs bound to an action is pressed (first frame held only),
+    /// or one of its sequences has been completed
+    ///
+    /// Actions that don't exist are never pressed
+    ///
+    /// usage:
+    /// ```
+    /// loop {
+    ///     engine.wait_frame(); // wait for next frame + captures input
+    ///
+    ///     if engine.is_action_pressed("quit") {
+    ///         break; // exits app
+    ///     }
+    /// }
+    /// ```
+    pub fn is_action_pressed(&self, action: &str) -> bool {
+        self.sequences.is_triggered(action)
+            || self
+                .actions
+                .get_bindings(action)
+                .iter()
+                .any(|binding| match *binding {
+                    Binding::Key(key, modifier) => {
+                        self.is_key_pressed_with_modifier(key, modifier, KeyEventKind::Press)
+                    }
+                    Binding::Mouse(button, modifier) => self
+                        .get_mouse_press_with_modifier(button, modifier)
+                        .is_some(),
+                })
+    }
+
+    /// checks whenever one of the inputs bound to an action is held down
+    pub fn is_action_held(&self, action: &str) -> bool {
+        self.actions
+            .get_bindings(action)
+            .iter()
+            .any(|binding| match *binding {
+                Binding::Key(key, modifier) => {
+                    self.is_key_held_with_modifier(key, modifier, KeyEventKind::Press)
+                }
+                Binding::Mouse(button, modifier) => self
+                    .get_mouse_held_with_modifier(button, modifier)
+                    .is_some(),
+            })
+    }
+
+    /// checks whenever one of the inputs bound to an action has been released (first frame released)
+    pub fn is_action_released(&self, action: &str) -> bool {
+        self.actions
+            .get_bindings(action)
+            .iter()
+            .any(|binding| match *binding {
+                Binding::Key(key, modifier) => {
+                    self.is_key_released_with_modifier(key, modifier, KeyEventKind::Release)
+                }
+                Binding::Mouse(button, modifier) => self
+                    .get_mouse_released_with_modifier(button, modifier)
+                    .is_some(),
+            })
+    }
+
+    /// Starts recording every input event and frame, see the [record](record/index.html) module
+    ///
+    /// A recording already in progress is discarded
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording::new());
+    }
+
+    /// Stops recording, giving the recording if one was in progress
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+
+    /// Replays a recording: until it's over, `wait_frame` and `poll` read their events from the recording instead of the terminal
+    ///
+    /// Each frame gets the events recorded during the same frame, regardless of the time.
+    /// With `real_time`, frames still wait for the target FPS, otherwise they are run as fast as possible.
+    ///
+    /// usage:
+    /// ```
+    /// use console_engine::record::Recording;
+    ///
+    /// let recording = Recording::load(&std::fs::read_to_string("session.rec").unwrap()).unwrap();
+    /// engine.replay(recording, true);
+    /// while engine.is_replaying() {
+    ///     engine.wait_frame();
+    ///     // do your stuff
+    /// }
+    /// ```
+    pub fn replay(&mut self, recording: Recording, real_time: bool) {
+        let replay = Replay::new(recording, real_time);
+        self.replay = (!replay.is_over()).then_some(replay);
+    }
+
+    /// Checks whenever a recording is being replayed
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Tells how key events are reported by the terminal
+    ///
+    /// With [KeyboardMode::Enhanced], key releases are reported as they happen
+    /// and holding a key down shows up as [KeyEventKind::Repeat] in [is_key_pressed_with_modifier](#method.is_key_pressed_with_modifier).
+    ///
+    /// With [KeyboardMode::Legacy], terminals only report key presses:
+    /// a key is held as long as the terminal repeats it, and released one frame after it stopped being repeated.
+    /// Depending on the keyboard's repeat delay, a held key may briefly look released.
+    ///
+    /// usage:
+    /// ```
+    /// use console_engine::backend::KeyboardMode;
+    ///
+    /// if engine.get_keyboard_mode() == KeyboardMode::Legacy {
+    ///     // toggle the action on key press instead of waiting for the key release
+    /// }
+    /// ```
+    pub fn get_keyboard_mode(&self) -> KeyboardMode {
+        self.backend.keyboard_mode()
+    }
+
+    /// Checks whenever the terminal has focus
+    ///
+    /// Useful to pause animations or lower the frame rate while the terminal is in the background.
+    /// Terminals that don't report focus changes are always considered focused.
+    ///
+    /// usage:
+    /// ```
+    /// if !engine.is_focused() {
+    ///     // skip the animation
+    /// }
+    /// ```
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Give the text pasted into the terminal during the last frame
+    ///
+    /// Pasted text doesn't generate key events, unless bracketed paste has been disabled with the [builder](#method.builder)
+    ///
+    /// usage:
+    /// ```
+    /// if let Some(text) = engine.get_paste() {
+    ///     text_field.put_str(text);
+    /// }
+    /// ```
+    pub fn get_paste(&self) -> Option<&str> {
+        self.paste.as_deref()
+    }
+
     /// Give the mouse's terminal coordinates if a button is held on the mouse
     ///
     /// usage:
@@ -1070,6 +1687,100 @@ impl ConsoleEngine {
         None
     }
 
+    /// Give the mouse's terminal coordinates and the number of successive clicks if the provided button has been pressed
+    ///
+    /// A click counts as a double click when the button has been pressed less than the [click interval](#method.set_click_interval) before,
+    /// at about the same place, and so on for triple clicks.
+    ///
+    /// usage:
+    /// ```
+    /// use console_engine::MouseButton;
+    ///
+    /// match engine.get_mouse_click(MouseButton::Left) {
+    ///     Some((_, y, 2)) => open_file(y),
+    ///     Some((_, y, 3)) => select_line(y),
+    ///     _ => {}
+    /// }
+    /// ```
+    pub fn get_mouse_click(&self, button: MouseButton) -> Option<(u32, u32, u8)> {
+        self.get_mouse_click_with_modifier(button, KeyModifiers::NONE)
+    }
+
+    /// Give the mouse's terminal coordinates and the number of successive clicks if the provided button + modifier (ctrl, shift, ...) has been pressed
+    pub fn get_mouse_click_with_modifier(
+        &self,
+        button: MouseButton,
+        modifier: KeyModifiers,
+    ) -> Option<(u32, u32, u8)> {
+        self.mouse.get_click(button, modifier)
+    }
+
+    /// Sets the maximum delay between two clicks of a double click (default: 500 milliseconds)
+    ///
+    /// The delay is converted into a number of frames at the target FPS
+    pub fn set_click_interval(&mut self, interval: std::time::Duration) {
+        self.click_interval = interval;
+    }
+
+    /// Sets how many cells the mouse may move between two clicks of a double click (default: 1)
+    pub fn set_click_tolerance(&mut self, tolerance: u32) {
+        self.click_tolerance = tolerance;
+    }
+
+    /// Give the last known terminal coordinates of the mouse
+    ///
+    /// The position is kept across frames, it's `None` until the terminal reported a first mouse event
+    ///
+    /// usage:
+    /// ```
+    /// if let Some((x, y)) = engine.get_mouse_position() {
+    ///     engine.set_pxl(x as i32, y as i32, pixel::pxl('+'));
+    /// }
+    /// ```
+    pub fn get_mouse_position(&self) -> Option<(u32, u32)> {
+        self.mouse.get_position()
+    }
+
+    /// checks whenever the mouse is over the given rectangle, borders included
+    ///
+    /// usage:
+    /// ```
+    /// let color = if engine.is_mouse_over(2, 2, 10, 4) { Color::Yellow } else { Color::White };
+    /// engine.rect(2, 2, 10, 4, pixel::pxl('#', Some(color), None, None));
+    /// ```
+    pub fn is_mouse_over(&self, start_x: i32, start_y: i32, end_x: i32, end_y: i32) -> bool {
+        self.mouse.get_position().is_some_and(|(x, y)| {
+            (start_x..=end_x).contains(&(x as i32)) && (start_y..=end_y).contains(&(y as i32))
+        })
+    }
+
+    /// Give the drag in progress, from the frame the mouse started moving with a button held
+    /// to the frame before the button is released
+    ///
+    /// usage:
+    /// ```
+    /// if let Some(drag) = engine.get_drag() {
+    ///     if drag.button == MouseButton::Left {
+    ///         let (dx, dy) = drag.delta;
+    ///         rect_x += dx;
+    ///         rect_y += dy;
+    ///     }
+    /// }
+    /// ```
+    pub fn get_drag(&self) -> Option<MouseDrag> {
+        self.mouse.get_drag()
+    }
+
+    /// Give the drag that started during the last frame
+    pub fn get_drag_start(&self) -> Option<MouseDrag> {
+        self.mouse.get_drag_start()
+    }
+
+    /// Give the drag that ended during the last frame, positioned where its button has been released
+    pub fn get_drag_end(&self) -> Option<MouseDrag> {
+        self.mouse.get_drag_end()
+    }
+
     /// checks whenever the mouse has been scrolled
     ///
     /// usage:
@@ -1111,16 +1822,18 @@ impl ConsoleEngine {
             }
         }
         if directions.is_empty() {
-            return None;
+            None
         } else {
-            return (directions, modifiers, mouse_pos).into();
+            (directions, modifiers, mouse_pos).into()
         }
     }
 }
 
 impl Drop for ConsoleEngine {
     /// gracefully stop the engine when dropping it
+    ///
+    /// Errors are ignored, dropping the engine never panics
     fn drop(&mut self) {
-        self.end();
+        self.end().ok();
     }
 }
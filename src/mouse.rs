@@ -0,0 +1,408 @@
+//! Mouse state tracked across frames
+//!
+//! The engine remembers where the mouse is and follows drags from the press of a button to its release,
+//! see [get_mouse_position](crate::ConsoleEngine::get_mouse_position) and [get_drag](crate::ConsoleEngine::get_drag).
+//!
+//! With [poll](crate::ConsoleEngine::poll), the drag and clicks of a frame are known once `Event::Frame` is returned,
+//! the position follows every mouse event.
+//!
+//! The mouse position is only known once the terminal reported a mouse event,
+//! and hovering needs a terminal reporting mouse moves, which most do while the mouse is captured.
+//!
+//! Successive presses of a button at the same place are counted as double or triple clicks,
+//! see [get_mouse_click](crate::ConsoleEngine::get_mouse_click).
+
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+/// A drag of the mouse, from the press of a button to its release
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseDrag {
+    /// Button held during the drag
+    pub button: MouseButton,
+    /// Where the button has been pressed
+    pub start: (u32, u32),
+    /// Current position of the mouse
+    pub position: (u32, u32),
+    /// Movement of the mouse during the last frame
+    pub delta: (i32, i32),
+}
+
+impl MouseDrag {
+    /// Get the movement of the mouse since the start of the drag
+    pub fn get_offset(&self) -> (i32, i32) {
+        offset(self.start, self.position)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Click {
+    button: MouseButton,
+    position: (u32, u32),
+    count: u8,
+    frame: usize,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct MouseTracker {
+    position: Option<(u32, u32)>,
+    last_click: Option<Click>,
+    /// clicks of the last frame, with their modifiers and click count
+    clicks: Vec<(MouseButton, KeyModifiers, (u32, u32), u8)>,
+    /// last button pressed and where, until it's released
+    press: Option<(MouseButton, (u32, u32))>,
+    drag: Option<MouseDrag>,
+    drag_started: bool,
+    drag_ended: Option<MouseDrag>,
+    /// position of the drag at the end of the previous frame
+    previous: Option<(u32, u32)>,
+    /// the states of the last frame are kept until the next event or the end of the next frame
+    frame_over: bool,
+}
+
+impl MouseTracker {
+    /// Follows the mouse events of a frame
+    ///
+    /// Clicks count as successive if they are at most `interval` frames apart and `tolerance` cells away from each other
+    pub(crate) fn update(
+        &mut self,
+        events: &[MouseEvent],
+        frame: usize,
+        interval: usize,
+        tolerance: u32,
+    ) {
+        for event in events {
+            self.handle(event, frame, interval, tolerance);
+        }
+        self.end_frame();
+    }
+
+    /// Clears the states of the last frame
+    fn start_frame(&mut self) {
+        self.previous = self.drag.map(|drag| drag.position);
+        self.drag_started = false;
+        self.drag_ended = None;
+        self.clicks.clear();
+        self.frame_over = false;
+    }
+
+    /// Follows a single mouse event, see [update](#method.update)
+    ///
+    /// Returns the number of successive clicks of a button press, 0 for other mouse events
+    pub(crate) fn handle(
+        &mut self,
+        event: &MouseEvent,
+        frame: usize,
+        interval: usize,
+        tolerance: u32,
+    ) -> u8 {
+        if self.frame_over {
+            self.start_frame();
+        }
+        let position = (event.column as u32, event.row as u32);
+        self.position = Some(position);
+        let count = self.count_clicks(event, frame, interval, tolerance);
+        if let MouseEventKind::Down(button) = event.kind {
+            self.clicks.push((button, event.modifiers, position, count));
+        }
+        match event.kind {
+            MouseEventKind::Down(button) if self.drag.is_none() => {
+                self.press = Some((button, position));
+            }
+            MouseEventKind::Drag(button) => match &mut self.drag {
+                Some(drag) if drag.button == button => drag.position = position,
+                Some(_) => {}
+                None => {
+                    // the press may have happened before the engine started
+                    let start = match self.press {
+                        Some((pressed, start)) if pressed == button => start,
+                        _ => position,
+                    };
+                    self.drag = Some(MouseDrag {
+                        button,
+                        start,
+                        position,
+                        delta: (0, 0),
+                    });
+                    self.drag_started = true;
+                }
+            },
+            MouseEventKind::Up(button) => {
+                if let Some(mut drag) = self.drag.filter(|drag| drag.button == button) {
+                    drag.position = position;
+                    self.drag_ended = Some(drag);
+                    self.drag = None;
+                }
+                if self.press.is_some_and(|(pressed, _)| pressed == button) {
+                    self.press = None;
+                }
+            }
+            _ => {}
+        }
+        count
+    }
+
+    /// Ends the frame, giving the movement of the drag during the frame
+    pub(crate) fn end_frame(&mut self) {
+        if self.frame_over {
+            self.start_frame();
+        }
+        let previous = self.previous;
+        if let Some(drag) = &mut self.drag {
+            let from = match previous {
+                Some(previous) if !self.drag_started => previous,
+                _ => drag.start,
+            };
+            drag.delta = offset(from, drag.position);
+        }
+        if let Some(drag) = &mut self.drag_ended {
+            drag.delta = offset(previous.unwrap_or(drag.start), drag.position);
+        }
+        self.frame_over = true;
+    }
+
+    /// Gives the number of successive clicks of a button press, 0 for other mouse events
+    fn count_clicks(
+        &mut self,
+        event: &MouseEvent,
+        frame: usize,
+        interval: usize,
+        tolerance: u32,
+    ) -> u8 {
+        let MouseEventKind::Down(button) = event.kind else {
+            return 0;
+        };
+        let position = (event.column as u32, event.row as u32);
+        let count = match self.last_click {
+            Some(last)
+                if last.button == button
+                    && frame <= last.frame.saturating_add(interval)
+                    && last.position.0.abs_diff(position.0) <= tolerance
+                    && last.position.1.abs_diff(position.1) <= tolerance =>
+            {
+                last.count.saturating_add(1)
+            }
+            _ => 1,
+        };
+        self.last_click = Some(Click {
+            button,
+            position,
+            count,
+            frame,
+        });
+        count
+    }
+
+    pub(crate) fn get_click(
+        &self,
+        button: MouseButton,
+        modifier: KeyModifiers,
+    ) -> Option<(u32, u32, u8)> {
+        self.clicks
+            .iter()
+            .find(|(b, m, _, _)| *b == button && *m == modifier)
+            .map(|(_, _, (x, y), count)| (*x, *y, *count))
+    }
+
+    pub(crate) fn get_position(&self) -> Option<(u32, u32)> {
+        self.position
+    }
+
+    pub(crate) fn get_drag(&self) -> Option<MouseDrag> {
+        self.drag
+    }
+
+    pub(crate) fn get_drag_start(&self) -> Option<MouseDrag> {
+        self.drag.filter(|_| self.drag_started)
+    }
+
+    pub(crate) fn get_drag_end(&self) -> Option<MouseDrag> {
+        self.drag_ended
+    }
+}
+
+fn offset(from: (u32, u32), to: (u32, u32)) -> (i32, i32) {
+    (to.0 as i32 - from.0 as i32, to.1 as i32 - from.1 as i32)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ConsoleEngine, MouseButton};
+    use crossterm::event::MouseEventKind;
+    use std::time::Duration;
+
+    #[test]
+    fn position_and_hover() {
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 5, 30).unwrap();
+        assert_eq!(engine.get_mouse_position(), None);
+        input.mouse(MouseEventKind::Moved, 2, 1);
+        input.mouse(MouseEventKind::Moved, 3, 2);
+        engine.wait_frame();
+        assert_eq!(engine.get_mouse_position(), Some((3, 2)));
+        assert!(engine.is_mouse_over(3, 2, 3, 2));
+        assert!(engine.is_mouse_over(0, 0, 5, 4));
+        assert!(!engine.is_mouse_over(4, 0, 9, 4));
+        // the position is kept until the mouse moves again
+        engine.wait_frame();
+        assert_eq!(engine.get_mouse_position(), Some((3, 2)));
+    }
+
+    #[test]
+    fn drag() {
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 5, 30).unwrap();
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 2, 1);
+        engine.wait_frame();
+        // a press alone isn't a drag
+        assert_eq!(engine.get_drag(), None);
+
+        input.mouse(MouseEventKind::Drag(MouseButton::Left), 3, 1);
+        input.mouse(MouseEventKind::Drag(MouseButton::Left), 4, 2);
+        engine.wait_frame();
+        let drag = engine.get_drag_start().unwrap();
+        assert_eq!(drag.button, MouseButton::Left);
+        assert_eq!((drag.start, drag.position), ((2, 1), (4, 2)));
+        assert_eq!(drag.delta, (2, 1));
+        assert_eq!(engine.get_drag(), Some(drag));
+
+        // the drag goes on without any movement
+        engine.wait_frame();
+        assert_eq!(engine.get_drag_start(), None);
+        let drag = engine.get_drag().unwrap();
+        assert_eq!(drag.delta, (0, 0));
+        assert_eq!(drag.get_offset(), (2, 1));
+
+        // other buttons don't interfere
+        input.mouse(MouseEventKind::Drag(MouseButton::Right), 5, 2);
+        input.mouse(MouseEventKind::Drag(MouseButton::Left), 1, 3);
+        engine.wait_frame();
+        assert_eq!(engine.get_drag().unwrap().delta, (-3, 1));
+
+        input.mouse(MouseEventKind::Up(MouseButton::Left), 1, 4);
+        engine.wait_frame();
+        assert_eq!(engine.get_drag(), None);
+        let drag = engine.get_drag_end().unwrap();
+        assert_eq!(drag.get_offset(), (-1, 3));
+        assert_eq!(drag.delta, (0, 1));
+        engine.wait_frame();
+        assert_eq!(engine.get_drag_end(), None);
+    }
+
+    #[test]
+    fn clicks() {
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 5, 10).unwrap();
+        engine.set_click_interval(Duration::from_millis(200));
+        assert_eq!(engine.get_mouse_click(MouseButton::Left), None);
+
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 2, 1);
+        engine.wait_frame();
+        assert_eq!(engine.get_mouse_click(MouseButton::Left), Some((2, 1, 1)));
+        assert_eq!(engine.get_mouse_click(MouseButton::Right), None);
+        input.mouse(MouseEventKind::Up(MouseButton::Left), 2, 1);
+        engine.wait_frame();
+        assert_eq!(engine.get_mouse_click(MouseButton::Left), None);
+
+        // moving by one cell is tolerated
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 3, 1);
+        engine.wait_frame();
+        assert_eq!(engine.get_mouse_click(MouseButton::Left), Some((3, 1, 2)));
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 3, 1);
+        engine.wait_frame();
+        assert_eq!(engine.get_mouse_click(MouseButton::Left), Some((3, 1, 3)));
+
+        // 200ms is 2 frames at 10 fps
+        engine.wait_frame();
+        engine.wait_frame();
+        engine.wait_frame();
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 3, 1);
+        engine.wait_frame();
+        assert_eq!(engine.get_mouse_click(MouseButton::Left), Some((3, 1, 1)));
+
+        // too far away
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 5, 1);
+        engine.wait_frame();
+        assert_eq!(engine.get_mouse_click(MouseButton::Left), Some((5, 1, 1)));
+        engine.set_click_tolerance(2);
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 3, 1);
+        engine.wait_frame();
+        assert_eq!(engine.get_mouse_click(MouseButton::Left), Some((3, 1, 2)));
+
+        // another button starts over
+        input.mouse(MouseEventKind::Down(MouseButton::Right), 3, 1);
+        engine.wait_frame();
+        assert_eq!(engine.get_mouse_click(MouseButton::Right), Some((3, 1, 1)));
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 3, 1);
+        engine.wait_frame();
+        assert_eq!(engine.get_mouse_click(MouseButton::Left), Some((3, 1, 1)));
+    }
+
+    #[cfg(feature = "event")]
+    #[test]
+    fn poll_clicks() {
+        use crate::events::Event;
+
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 5, 30).unwrap();
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 2, 1);
+        input.mouse(MouseEventKind::Up(MouseButton::Left), 2, 1);
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 2, 1);
+        let mut clicks = vec![];
+        for _ in 0..3 {
+            match engine.poll() {
+                Event::Mouse(event) => clicks.push((event.kind, event.clicks)),
+                event => panic!("unexpected event {:?}", event),
+            }
+        }
+        assert_eq!(
+            clicks,
+            vec![
+                (MouseEventKind::Down(MouseButton::Left), 1),
+                (MouseEventKind::Up(MouseButton::Left), 0),
+                (MouseEventKind::Down(MouseButton::Left), 2),
+            ]
+        );
+    }
+
+    #[cfg(feature = "event")]
+    #[test]
+    fn poll_drag() {
+        use crate::events::Event;
+
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 5, 30).unwrap();
+        let poll_frame = |engine: &mut ConsoleEngine| {
+            let mut events = 0;
+            while !matches!(engine.poll(), Event::Frame) {
+                events += 1;
+            }
+            events
+        };
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 2, 1);
+        input.mouse(MouseEventKind::Drag(MouseButton::Left), 3, 1);
+        assert!(matches!(engine.poll(), Event::Mouse(_)));
+        // the position follows each event
+        assert_eq!(engine.get_mouse_position(), Some((2, 1)));
+        assert_eq!(poll_frame(&mut engine), 1);
+        assert_eq!(engine.get_mouse_position(), Some((3, 1)));
+        assert_eq!(engine.get_mouse_click(MouseButton::Left), Some((2, 1, 1)));
+        let drag = engine.get_drag_start().unwrap();
+        assert_eq!(
+            (drag.start, drag.position, drag.delta),
+            ((2, 1), (3, 1), (1, 0))
+        );
+
+        input.mouse(MouseEventKind::Drag(MouseButton::Left), 5, 2);
+        assert_eq!(poll_frame(&mut engine), 1);
+        assert_eq!(engine.get_drag_start(), None);
+        assert_eq!(engine.get_mouse_click(MouseButton::Left), None);
+        assert_eq!(engine.get_drag().unwrap().delta, (2, 1));
+
+        // the states of a frame without mouse events are cleared as well
+        assert_eq!(poll_frame(&mut engine), 0);
+        assert_eq!(engine.get_drag().unwrap().delta, (0, 0));
+
+        input.mouse(MouseEventKind::Up(MouseButton::Left), 6, 2);
+        assert_eq!(poll_frame(&mut engine), 1);
+        assert_eq!(engine.get_drag(), None);
+        let drag = engine.get_drag_end().unwrap();
+        assert_eq!((drag.get_offset(), drag.delta), ((4, 1), (1, 0)));
+        assert_eq!(poll_frame(&mut engine), 0);
+        assert_eq!(engine.get_drag_end(), None);
+    }
+}
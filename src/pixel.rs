@@ -1,31 +1,121 @@
 //! Character and color management
 
-use crossterm::style::Color;
+use crossterm::style::{Attribute, Color};
 use std::default;
+use std::ops::RangeInclusive;
 
 /// # Style
-/// contains boolean data for whether pixels should be bold, italic and underlined
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// contains the text attributes of pixels: bold, dim, italic, underlined, blink, reverse, hidden and strikethrough,
+/// along with the shape of the underline (single, double, curly or dotted, see [UnderlineStyle]) and its color
+///
+/// Terminals not supporting an attribute simply ignore it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
 pub struct Style {
     pub bold: bool,
     pub italic: bool,
     pub underlined: bool,
     pub strikethrough: bool,
+    /// Faint text, the opposite of bold
+    pub dim: bool,
+    pub blink: bool,
+    /// Swapped foreground and background colors
+    pub reverse: bool,
+    /// Invisible text, still taking its cells
+    pub hidden: bool,
+    /// Shape of the underline, only used when `underlined` is set
+    pub underline_style: UnderlineStyle,
+    /// Color of the underline, `None` uses the foreground color
+    pub underline_color: Option<Color>,
 }
-impl default::Default for Style {
-    fn default() -> Self {
-        Style {
-            bold: false,
-            italic: false,
-            underlined: false,
-            strikethrough: false,
+
+/// # UnderlineStyle
+/// shape of the line drawn under underlined pixels
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum UnderlineStyle {
+    #[default]
+    Single,
+    Double,
+    Curly,
+    Dotted,
+}
+
+impl Style {
+    /// Terminal attributes turning the `current` style into this one, ignoring the underline color
+    pub(crate) fn attributes_from(&self, current: &Style) -> Vec<Attribute> {
+        let mut attributes = vec![];
+        // bold and dim can only be turned off together
+        // (Attribute::NoBold is a double underline on most terminals)
+        let mut intensity = (current.bold, current.dim);
+        if (intensity.0 && !self.bold) || (intensity.1 && !self.dim) {
+            attributes.push(Attribute::NormalIntensity);
+            intensity = (false, false);
+        }
+        if self.bold && !intensity.0 {
+            attributes.push(Attribute::Bold);
+        }
+        if self.dim && !intensity.1 {
+            attributes.push(Attribute::Dim);
+        }
+        if self.italic != current.italic {
+            attributes.push(if self.italic {
+                Attribute::Italic
+            } else {
+                Attribute::NoItalic
+            });
+        }
+        let underline = |style: &Style| style.underlined.then_some(style.underline_style);
+        if underline(self) != underline(current) {
+            attributes.push(match underline(self) {
+                None => Attribute::NoUnderline,
+                Some(UnderlineStyle::Single) => Attribute::Underlined,
+                Some(UnderlineStyle::Double) => Attribute::DoubleUnderlined,
+                Some(UnderlineStyle::Curly) => Attribute::Undercurled,
+                Some(UnderlineStyle::Dotted) => Attribute::Underdotted,
+            });
+        }
+        if self.blink != current.blink {
+            attributes.push(if self.blink {
+                Attribute::SlowBlink
+            } else {
+                Attribute::NoBlink
+            });
+        }
+        if self.reverse != current.reverse {
+            attributes.push(if self.reverse {
+                Attribute::Reverse
+            } else {
+                Attribute::NoReverse
+            });
+        }
+        if self.hidden != current.hidden {
+            attributes.push(if self.hidden {
+                Attribute::Hidden
+            } else {
+                Attribute::NoHidden
+            });
+        }
+        if self.strikethrough != current.strikethrough {
+            attributes.push(if self.strikethrough {
+                Attribute::CrossedOut
+            } else {
+                Attribute::NotCrossedOut
+            });
         }
+        attributes
     }
 }
 
 /// # Pixel structure
 /// contains color data and character data
-#[derive(Clone, Eq, PartialEq, Copy)]
+///
+/// Grapheme clusters made of several characters (flags, emojis with skin tones, letters with combining accents...)
+/// don't fit in a `char`: they are kept by the [Screen](crate::screen::Screen) holding the Pixel,
+/// see [set_grapheme](crate::screen::Screen::set_grapheme). `chr` then refers to the cluster with a character of
+/// the Supplementary Private Use Area-B (U+100000 to U+10FFFD), see [is_cluster](#method.is_cluster).
+///
+/// Hyperlinks are not part of a Pixel either: they are attached to the cells of a Screen,
+/// see [get_link](crate::screen::Screen::get_link).
+#[derive(Debug, Clone, Eq, PartialEq, Copy)]
 pub struct Pixel {
     /// Foreground color of the Pixel
     pub fg: Color,
@@ -33,7 +123,7 @@ pub struct Pixel {
     pub bg: Color,
     /// Character of the Pixel
     pub chr: char,
-    /// Whether the Pixel is bold, italic or underlined
+    /// Text attributes of the Pixel, see [Style]
     pub style: Style,
 }
 impl Pixel {
@@ -44,7 +134,36 @@ impl Pixel {
     pub fn get_style(&self) -> Style {
         self.style
     }
+    /// checks whenever the Pixel is the right half of a wide character (see [CONTINUATION])
+    pub fn is_continuation(&self) -> bool {
+        self.chr == CONTINUATION
+    }
+    /// checks whenever the Pixel refers to a grapheme cluster kept by its [Screen](crate::screen::Screen)
+    pub fn is_cluster(&self) -> bool {
+        NARROW_CLUSTERS.contains(&self.chr) || WIDE_CLUSTERS.contains(&self.chr)
+    }
+    /// checks whenever the Pixel's character takes two columns on a terminal (e.g. CJK characters, most emojis)
+    pub fn is_wide(&self) -> bool {
+        WIDE_CLUSTERS.contains(&self.chr)
+            || unicode_width::UnicodeWidthChar::width(self.chr) == Some(2)
+    }
 }
+
+/// Character stored in the cell covered by the right half of a wide character
+///
+/// Screens keep wide characters and their continuation together:
+/// setting a wide character also sets the continuation on its right,
+/// and overwriting either half of a wide character blanks the other half.
+///
+/// This is a noncharacter, so it never appears in regular text.
+pub const CONTINUATION: char = '\u{FFFF}';
+
+/// Characters referring to the grapheme clusters taking a single column, see [Pixel]
+pub(crate) const NARROW_CLUSTERS: RangeInclusive<char> = '\u{100000}'..='\u{107FFF}';
+
+/// Characters referring to the grapheme clusters taking two columns, see [Pixel]
+pub(crate) const WIDE_CLUSTERS: RangeInclusive<char> = '\u{108000}'..='\u{10FFFD}';
+
 impl default::Default for Pixel {
     fn default() -> Self {
         Pixel {
@@ -69,10 +188,15 @@ pub fn pxl(chr: char, fg: Option<Color>, bg: Option<Color>, style: Option<Style>
         fg: fg.unwrap_or(Color::Reset),
         bg: bg.unwrap_or(Color::Reset),
         chr,
-        style: style.unwrap_or(Style::default()),
+        style: style.unwrap_or_default(),
     }
 }
 
+/// Number of columns taken by a grapheme on a terminal, between 0 and 2
+pub(crate) fn grapheme_width(grapheme: &str) -> usize {
+    unicode_width::UnicodeWidthStr::width(grapheme).min(2)
+}
+
 pub fn pxl_plain(char: char) -> Pixel {
     Pixel {
         fg: Color::Reset,
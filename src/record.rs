@@ -0,0 +1,536 @@
+//! Recording and replay of input sessions
+//!
+//! While recording, the engine keeps every event read by `wait_frame` or `poll`, along with the frame boundaries.
+//! Replaying the recording into an engine gives each frame the exact same events,
+//! so the application goes through the same states for the same `frame_count`,
+//! whatever the speed of the machine replaying it.
+//!
+//! Recordings are saved as text, one entry per line, starting with the number of milliseconds since the recording started:
+//!
+//! ```text
+//! # console_engine recording
+//! 0 key ctrl+x
+//! 12 key a release
+//! 33 frame
+//! 40 mouse down-left 3 2
+//! 41 mouse scroll-up 3 2 ctrl+shift
+//! 50 resize 20 6
+//! 51 focus out
+//! 52 paste hello\nworld
+//! 66 frame
+//! ```
+//!
+//! - `key` entries write the key as in the [action map config](crate::actions), followed by `repeat` or `release` if it isn't a press.
+//! - `mouse` entries give the kind of event (`down-<button>`, `up-<button>`, `drag-<button>`, `moved`, `scroll-<direction>`),
+//!   the column, the row, then the modifiers if any.
+//! - `paste` entries escape backslashes, control characters and newlines as `\\`, `\n`, `\r`, `\t` or `\u{hex}`.
+//! - Lines starting with `#` are comments.
+//!
+//! Timestamps are informative only, replays follow the frames.
+//! Clipboard answers aren't recorded, nor are keys that can't be written in the config format (media keys, modifier keys pressed alone...).
+//!
+//! usage:
+//! ```no_run
+//! use console_engine::record::Recording;
+//!
+//! # let mut engine = console_engine::ConsoleEngine::init(20, 10, 30).unwrap();
+//! engine.start_recording();
+//! // ... run the application
+//! if let Some(recording) = engine.stop_recording() {
+//!     std::fs::write("session.rec", recording.save()).unwrap();
+//! }
+//!
+//! // later, to reproduce the session
+//! let recording = Recording::load(&std::fs::read_to_string("session.rec").unwrap()).unwrap();
+//! engine.replay(recording, false);
+//! ```
+
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{
+    Event, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+use crate::actions::{Binding, MODIFIERS};
+
+const BUTTONS: [(MouseButton, &str); 3] = [
+    (MouseButton::Left, "left"),
+    (MouseButton::Right, "right"),
+    (MouseButton::Middle, "middle"),
+];
+
+const SCROLLS: [(MouseEventKind, &str); 4] = [
+    (MouseEventKind::ScrollUp, "up"),
+    (MouseEventKind::ScrollDown, "down"),
+    (MouseEventKind::ScrollLeft, "left"),
+    (MouseEventKind::ScrollRight, "right"),
+];
+
+#[derive(Debug, Clone)]
+struct Entry {
+    /// time since the recording started
+    time: Duration,
+    /// `None` marks the end of a frame
+    event: Option<Event>,
+}
+
+/// A recorded input session, see the [module documentation](index.html)
+#[derive(Debug, Clone)]
+pub struct Recording {
+    entries: Vec<Entry>,
+    start: Instant,
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self {
+            entries: vec![],
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Recording {
+    /// Creates an empty recording, its timestamps start now
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an event to the current frame
+    ///
+    /// Keys without a name in the [config format](crate::actions) are left out, they couldn't be loaded back
+    pub fn push_event(&mut self, event: Event) {
+        if let Event::Key(key) = &event {
+            if !Binding::Key(key.code, key.modifiers).has_name() {
+                return;
+            }
+        }
+        self.push(Some(event));
+    }
+
+    /// Ends the current frame
+    pub fn push_frame(&mut self) {
+        self.push(None);
+    }
+
+    fn push(&mut self, event: Option<Event>) {
+        self.entries.push(Entry {
+            time: self.start.elapsed(),
+            event,
+        });
+    }
+
+    /// Get the number of frames in the recording
+    pub fn get_frame_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.event.is_none())
+            .count()
+    }
+
+    /// Reads a recording saved with [save](#method.save)
+    ///
+    /// Returns a message telling which line is invalid if the text can't be parsed
+    pub fn load(text: &str) -> Result<Recording, String> {
+        let mut recording = Recording::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = parse_entry(line).map_err(|err| format!("line {}: {}", index + 1, err))?;
+            recording.entries.push(entry);
+        }
+        Ok(recording)
+    }
+
+    /// Writes the recording as text, which can be read back with [load](#method.load)
+    pub fn save(&self) -> String {
+        let mut text = String::from("# console_engine recording\n");
+        for entry in self.entries.iter() {
+            text.push_str(&format!("{} ", entry.time.as_millis()));
+            match &entry.event {
+                None => text.push_str("frame"),
+                Some(event) => write_event(&mut text, event),
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// A recording being replayed by the engine
+#[derive(Debug)]
+pub(crate) struct Replay {
+    events: VecDeque<Option<Event>>,
+    /// wait for the target FPS between frames instead of running them as fast as possible
+    pub(crate) real_time: bool,
+}
+
+impl Replay {
+    pub(crate) fn new(recording: Recording, real_time: bool) -> Self {
+        Self {
+            events: recording
+                .entries
+                .into_iter()
+                .map(|entry| entry.event)
+                .collect(),
+            real_time,
+        }
+    }
+
+    /// Gives the next event, `None` at the end of a frame
+    pub(crate) fn next_event(&mut self) -> Option<Event> {
+        self.events.pop_front().flatten()
+    }
+
+    pub(crate) fn is_over(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+fn write_event(text: &mut String, event: &Event) {
+    match event {
+        Event::Key(key) => {
+            text.push_str(&format!("key {}", Binding::Key(key.code, key.modifiers)));
+            match key.kind {
+                KeyEventKind::Press => {}
+                KeyEventKind::Repeat => text.push_str(" repeat"),
+                KeyEventKind::Release => text.push_str(" release"),
+            }
+        }
+        Event::Mouse(mouse) => {
+            let kind = match mouse.kind {
+                MouseEventKind::Down(button) => format!("down-{}", button_name(button)),
+                MouseEventKind::Up(button) => format!("up-{}", button_name(button)),
+                MouseEventKind::Drag(button) => format!("drag-{}", button_name(button)),
+                MouseEventKind::Moved => String::from("moved"),
+                scroll => {
+                    let (_, name) = SCROLLS.iter().find(|(kind, _)| *kind == scroll).unwrap();
+                    format!("scroll-{}", name)
+                }
+            };
+            text.push_str(&format!("mouse {} {} {}", kind, mouse.column, mouse.row));
+            let modifiers: Vec<&str> = MODIFIERS
+                .iter()
+                .filter(|(modifier, _)| mouse.modifiers.contains(*modifier))
+                .map(|(_, name)| *name)
+                .collect();
+            if !modifiers.is_empty() {
+                text.push_str(&format!(" {}", modifiers.join("+")));
+            }
+        }
+        Event::Resize(width, height) => text.push_str(&format!("resize {} {}", width, height)),
+        Event::FocusGained => text.push_str("focus in"),
+        Event::FocusLost => text.push_str("focus out"),
+        Event::Paste(pasted) => {
+            text.push_str("paste ");
+            for c in pasted.chars() {
+                match c {
+                    '\\' => text.push_str("\\\\"),
+                    '\n' => text.push_str("\\n"),
+                    '\r' => text.push_str("\\r"),
+                    '\t' => text.push_str("\\t"),
+                    c if c.is_control() => text.push_str(&format!("\\u{{{:x}}}", c as u32)),
+                    c => text.push(c),
+                }
+            }
+        }
+    }
+}
+
+fn parse_entry(line: &str) -> Result<Entry, String> {
+    let (time, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let time = time
+        .parse::<u64>()
+        .map_err(|_| format!("invalid timestamp `{}`", time))?;
+    let (kind, args) = rest.split_once(' ').unwrap_or((rest, ""));
+    let event = match kind {
+        "frame" => None,
+        "key" => Some(parse_key(args)?),
+        "mouse" => Some(parse_mouse(args)?),
+        "resize" => {
+            let size: Vec<u16> = args
+                .split_whitespace()
+                .map(u16::from_str)
+                .collect::<Result<_, _>>()
+                .map_err(|_| format!("invalid size `{}`", args))?;
+            match size[..] {
+                [width, height] => Some(Event::Resize(width, height)),
+                _ => return Err(format!("invalid size `{}`", args)),
+            }
+        }
+        "focus" => match args {
+            "in" => Some(Event::FocusGained),
+            "out" => Some(Event::FocusLost),
+            _ => return Err(format!("invalid focus `{}`", args)),
+        },
+        "paste" => Some(Event::Paste(unescape(args)?)),
+        _ => return Err(format!("unknown entry `{}`", kind)),
+    };
+    Ok(Entry {
+        time: Duration::from_millis(time),
+        event,
+    })
+}
+
+fn parse_key(args: &str) -> Result<Event, String> {
+    let (key, kind) = args.split_once(' ').unwrap_or((args, ""));
+    let kind = match kind {
+        "" => KeyEventKind::Press,
+        "repeat" => KeyEventKind::Repeat,
+        "release" => KeyEventKind::Release,
+        _ => return Err(format!("invalid key kind `{}`", kind)),
+    };
+    match Binding::from_str(key)? {
+        Binding::Key(code, modifiers) => {
+            Ok(Event::Key(KeyEvent::new_with_kind(code, modifiers, kind)))
+        }
+        Binding::Mouse(..) => Err(format!("invalid key `{}`", key)),
+    }
+}
+
+fn parse_mouse(args: &str) -> Result<Event, String> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let (kind, column, row, modifiers) = match parts[..] {
+        [kind, column, row] => (kind, column, row, None),
+        [kind, column, row, modifiers] => (kind, column, row, Some(modifiers)),
+        _ => return Err(format!("invalid mouse event `{}`", args)),
+    };
+    let button = |name: &str| {
+        BUTTONS
+            .iter()
+            .find(|(_, button)| *button == name)
+            .map(|(button, _)| *button)
+    };
+    let kind = match kind.split_once('-') {
+        None if kind == "moved" => Some(MouseEventKind::Moved),
+        Some(("down", name)) => button(name).map(MouseEventKind::Down),
+        Some(("up", name)) => button(name).map(MouseEventKind::Up),
+        Some(("drag", name)) => button(name).map(MouseEventKind::Drag),
+        Some(("scroll", name)) => SCROLLS
+            .iter()
+            .find(|(_, scroll)| *scroll == name)
+            .map(|(kind, _)| *kind),
+        _ => None,
+    }
+    .ok_or_else(|| format!("invalid mouse event `{}`", kind))?;
+    let column = column
+        .parse()
+        .map_err(|_| format!("invalid column `{}`", column))?;
+    let row = row.parse().map_err(|_| format!("invalid row `{}`", row))?;
+    let mut mouse_modifiers = KeyModifiers::NONE;
+    for name in modifiers.into_iter().flat_map(|m| m.split('+')) {
+        match MODIFIERS.iter().find(|(_, modifier)| *modifier == name) {
+            Some((modifier, _)) => mouse_modifiers |= *modifier,
+            None => return Err(format!("unknown modifier `{}`", name)),
+        }
+    }
+    Ok(Event::Mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: mouse_modifiers,
+    }))
+}
+
+fn button_name(button: MouseButton) -> &'static str {
+    let (_, name) = BUTTONS.iter().find(|(b, _)| *b == button).unwrap();
+    name
+}
+
+fn unescape(text: &str) -> Result<String, String> {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some('u') => {
+                let code: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                let c = code
+                    .strip_prefix('{')
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("invalid escape sequence `\\u{}}}`", code))?;
+                unescaped.push(c);
+            }
+            _ => return Err(String::from("invalid escape sequence")),
+        }
+    }
+    Ok(unescaped)
+}
+
+#[cfg(test)]
+mod test {
+    use super::Recording;
+    use crate::{ConsoleEngine, KeyCode, KeyEventKind, KeyModifiers, MouseButton};
+    use crossterm::event::{Event, KeyEvent, MouseEvent, MouseEventKind};
+
+    #[test]
+    fn load_and_save() {
+        let text = "\
+# console_engine recording
+0 key ctrl+x
+12 key A release
+20 key Space repeat
+33 frame
+40 mouse down-left 3 2
+41 mouse scroll-up 3 2 ctrl+shift
+50 resize 20 6
+51 focus out
+52 paste a\\\\b\\nc\\u{1b}d é
+66 frame
+";
+        let recording = Recording::load(text).unwrap();
+        assert_eq!(recording.get_frame_count(), 2);
+        assert_eq!(recording.save(), text);
+        let events: Vec<Option<Event>> = recording
+            .entries
+            .iter()
+            .map(|entry| entry.event.clone())
+            .collect();
+        assert_eq!(
+            events[..3],
+            [
+                Some(Event::Key(KeyEvent::new(
+                    KeyCode::Char('x'),
+                    KeyModifiers::CONTROL
+                ))),
+                Some(Event::Key(KeyEvent::new_with_kind(
+                    KeyCode::Char('A'),
+                    KeyModifiers::NONE,
+                    KeyEventKind::Release
+                ))),
+                Some(Event::Key(KeyEvent::new_with_kind(
+                    KeyCode::Char(' '),
+                    KeyModifiers::NONE,
+                    KeyEventKind::Repeat
+                ))),
+            ]
+        );
+        assert_eq!(
+            events[5],
+            Some(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::ScrollUp,
+                column: 3,
+                row: 2,
+                modifiers: KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+            }))
+        );
+        assert_eq!(
+            events[8],
+            Some(Event::Paste(String::from("a\\b\nc\x1bd é")))
+        );
+
+        assert_eq!(
+            Recording::load("0 frame\n10 key ctrl+").unwrap_err(),
+            "line 2: unknown key `` in `ctrl+`"
+        );
+        assert_eq!(
+            Recording::load("x frame").unwrap_err(),
+            "line 1: invalid timestamp `x`"
+        );
+        assert_eq!(
+            Recording::load("0 mouse down-back 1 1").unwrap_err(),
+            "line 1: invalid mouse event `down-back`"
+        );
+    }
+
+    #[test]
+    fn unnamed_keys() {
+        use crossterm::event::{MediaKeyCode, ModifierKeyCode};
+
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let mut recording = Recording::new();
+        for code in [
+            KeyCode::Media(MediaKeyCode::Play),
+            KeyCode::Modifier(ModifierKeyCode::LeftShift),
+            KeyCode::KeypadBegin,
+            KeyCode::Null,
+            KeyCode::F(30),
+            KeyCode::Char('\t'),
+        ] {
+            recording.push_event(key(code));
+        }
+        recording.push_event(key(KeyCode::F(12)));
+        recording.push_event(key(KeyCode::Char(' ')));
+        recording.push_frame();
+
+        let loaded = Recording::load(&recording.save()).unwrap();
+        let events: Vec<Option<Event>> = loaded
+            .entries
+            .iter()
+            .map(|entry| entry.event.clone())
+            .collect();
+        assert_eq!(
+            events,
+            [
+                Some(key(KeyCode::F(12))),
+                Some(key(KeyCode::Char(' '))),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 3, 30).unwrap();
+        engine.start_recording();
+        input.press_key(KeyCode::Char('a'));
+        input.mouse(MouseEventKind::Down(MouseButton::Left), 3, 2);
+        engine.wait_frame();
+        engine.wait_frame();
+        input.press_key(KeyCode::Char('b'));
+        input.resize(12, 4);
+        engine.wait_frame();
+        let recording = engine.stop_recording().unwrap();
+        assert!(engine.stop_recording().is_none());
+        assert_eq!(recording.get_frame_count(), 3);
+
+        let recording = Recording::load(&recording.save()).unwrap();
+        let (mut engine, _) = ConsoleEngine::init_headless(10, 3, 30).unwrap();
+        engine.replay(recording, false);
+        assert!(engine.is_replaying());
+        engine.wait_frame();
+        assert_eq!(engine.frame_count, 1);
+        assert!(engine.is_key_pressed(KeyCode::Char('a')));
+        assert_eq!(engine.get_mouse_press(MouseButton::Left), Some((3, 2)));
+        engine.wait_frame();
+        assert!(!engine.is_key_held(KeyCode::Char('a')));
+        engine.wait_frame();
+        assert!(engine.is_key_pressed(KeyCode::Char('b')));
+        assert_eq!(engine.get_resize(), Some((12, 4)));
+        // the recording is over, inputs come from the backend again
+        assert!(!engine.is_replaying());
+    }
+
+    #[test]
+    #[cfg(feature = "event")]
+    fn replay_events() {
+        use crate::events;
+
+        let (mut engine, input) = ConsoleEngine::init_headless(10, 3, 30).unwrap();
+        engine.start_recording();
+        input.press_key(KeyCode::Char('a'));
+        assert!(matches!(engine.poll(), events::Event::Key(_)));
+        assert!(matches!(engine.poll(), events::Event::Frame));
+        assert!(matches!(engine.poll(), events::Event::Frame));
+        let recording = engine.stop_recording().unwrap();
+        assert_eq!(recording.get_frame_count(), 2);
+
+        let (mut engine, _) = ConsoleEngine::init_headless(10, 3, 30).unwrap();
+        engine.replay(recording, false);
+        assert!(matches!(engine.poll(), events::Event::Key(_)));
+        assert!(matches!(engine.poll(), events::Event::Frame));
+        assert!(matches!(engine.poll(), events::Event::Frame));
+        assert_eq!(engine.frame_count, 2);
+    }
+}
@@ -1,7 +1,12 @@
 //! Standalone screens
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::Write;
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicU64, Ordering};
+use crate::backend::osc8;
 use crate::rect_style::BorderStyle;
+use unicode_segmentation::UnicodeSegmentation;
 
 use super::crossterm::style::Color;
 use super::crossterm::{queue, style};
@@ -12,13 +17,127 @@ use super::pixel::Pixel;
 ///
 /// A standalone structure that provides every drawing function that ConsoleEngine provides.
 ///
-/// You can get the full content of the screen via the [draw](#method.draw) method.
+/// You can get the full content of the screen via the [draw](#method.draw) method,
+/// or write it anywhere else with [render_to](#method.render_to).
 #[derive(Clone)]
 pub struct Screen {
     width: u32,
     height: u32,
     screen: Vec<Pixel>,
     empty: bool,
+    /// hyperlink of each cell
+    cell_links: Vec<Option<LinkId>>,
+    links: Links,
+    clusters: Clusters,
+}
+
+/// Identifier of a hyperlink target kept by a Screen
+///
+/// Identifiers are never reused, so a cell copied to another screen can't point to the wrong target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct LinkId(NonZeroU64);
+
+impl LinkId {
+    fn new() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        LinkId(NonZeroU64::new(NEXT.fetch_add(1, Ordering::Relaxed)).unwrap())
+    }
+}
+
+/// Hyperlink targets of the cells of a Screen
+#[derive(Clone, Default)]
+struct Links {
+    targets: BTreeMap<LinkId, String>,
+    /// number of targets from which the unused ones are dropped before adding another one
+    limit: usize,
+}
+
+impl Links {
+    const MIN_LIMIT: usize = 16;
+
+    fn get(&self, id: LinkId) -> Option<&str> {
+        self.targets.get(&id).map(String::as_str)
+    }
+
+    /// Returns the identifier of a target, registering it if needed
+    fn id(&mut self, target: &str, cell_links: &[Option<LinkId>]) -> LinkId {
+        if let Some((id, _)) = self.targets.iter().find(|(_, known)| *known == target) {
+            return *id;
+        }
+        let id = LinkId::new();
+        self.insert(id, target, cell_links);
+        id
+    }
+
+    /// Registers a target under the given identifier, e.g. when copying pixels from another screen
+    fn insert(&mut self, id: LinkId, target: &str, cell_links: &[Option<LinkId>]) {
+        if self.targets.contains_key(&id) {
+            return;
+        }
+        if self.targets.len() >= self.limit.max(Self::MIN_LIMIT) {
+            let used: BTreeSet<LinkId> = cell_links.iter().flatten().copied().collect();
+            self.targets.retain(|id, _| used.contains(id));
+            self.limit = self.targets.len() * 2;
+        }
+        self.targets.insert(id, String::from(target));
+    }
+}
+
+/// Grapheme clusters made of several characters, referred to by the pixels of a Screen
+///
+/// Each cluster is given a character of [NARROW_CLUSTERS](pixel::NARROW_CLUSTERS) or
+/// [WIDE_CLUSTERS](pixel::WIDE_CLUSTERS) depending on its width, so pixels still know whether they are wide.
+#[derive(Clone, Default)]
+struct Clusters {
+    graphemes: BTreeMap<char, String>,
+    chars: BTreeMap<String, char>,
+    /// number of clusters from which the unused ones are dropped before adding another one
+    limit: usize,
+}
+
+impl Clusters {
+    const MIN_LIMIT: usize = 256;
+
+    fn get(&self, chr: char) -> Option<&str> {
+        self.graphemes.get(&chr).map(String::as_str)
+    }
+
+    /// Returns the character to store in a Pixel for the first grapheme of a string, registering it if needed
+    ///
+    /// Falls back to the first character of the grapheme if every character for its width is used.
+    fn chr(&mut self, grapheme: &str, pixels: &[Pixel]) -> char {
+        let grapheme = grapheme.graphemes(true).next().unwrap_or(" ");
+        let mut chars = grapheme.chars();
+        let first = chars.next().unwrap_or(' ');
+        // single characters are stored as is, unless they would be taken for a cluster
+        if chars.next().is_none() && !pixel::pxl_plain(first).is_cluster() {
+            return first;
+        }
+        if let Some(chr) = self.chars.get(grapheme) {
+            return *chr;
+        }
+        if self.graphemes.len() >= self.limit.max(Self::MIN_LIMIT) {
+            let used: BTreeSet<char> = pixels
+                .iter()
+                .filter(|pixel| pixel.is_cluster())
+                .map(|pixel| pixel.chr)
+                .collect();
+            self.graphemes.retain(|chr, _| used.contains(chr));
+            self.chars.retain(|_, chr| used.contains(chr));
+            self.limit = self.graphemes.len() * 2;
+        }
+        let mut range = if pixel::grapheme_width(grapheme) == 2 {
+            pixel::WIDE_CLUSTERS
+        } else {
+            pixel::NARROW_CLUSTERS
+        };
+        let Some(chr) = range.find(|chr| !self.graphemes.contains_key(chr)) else {
+            return first;
+        };
+        self.graphemes.insert(chr, String::from(grapheme));
+        self.chars.insert(String::from(grapheme), chr);
+        chr
+    }
 }
 
 /// # Basic Usage :
@@ -59,28 +178,44 @@ impl Screen {
 
     /// Creates a new Screen object with the provided width and height filled with a specific Pixel
     pub fn new_fill(width: u32, height: u32, pixel: Pixel) -> Screen {
-        Screen {
+        let mut screen = Screen {
             width,
             height,
-            screen: vec![pixel; (width * height) as usize],
+            screen: vec![],
             empty: false,
-        }
+            cell_links: vec![],
+            links: Links::default(),
+            clusters: Clusters::default(),
+        };
+        screen.fill(pixel);
+        screen
     }
 
     /// Creates a new Screen object with the provided Vec<Pixel> structure fitting the width and height parameters.
     /// The Vec length must correspond to width*height
+    ///
+    /// A wide character must be followed by its [continuation](crate::pixel::CONTINUATION) on the same row,
+    /// otherwise it is replaced by a space.
     pub fn from_vec(vec: Vec<Pixel>, width: u32, height: u32) -> Screen {
         assert!(vec.len() == (width*height) as usize, "The Vec structure must have the length corresponding to width*height (={}) but the given Vec has a length of {}.", width*height, vec.len());
-        Screen {
+        let mut screen = Screen {
             width,
             height,
+            cell_links: vec![None; vec.len()],
             screen: vec,
             empty: false,
-        }
+            links: Links::default(),
+            clusters: Clusters::default(),
+        };
+        screen.fit_wide_characters();
+        screen
     }
 
     /// Creates a new Screen object with the provided String and colors fitting the width and height parameters.
     /// The String length must correspond to width*height
+    ///
+    /// Each character takes a single cell, so wide characters are replaced by spaces unless they are followed
+    /// by a [continuation](crate::pixel::CONTINUATION), see [from_vec](#method.from_vec)
     pub fn from_string(string: String, fg: Color, bg: Color, width: u32, height: u32) -> Screen {
         assert!(string.chars().count() == (width*height) as usize, "The String must have the length corresponding to width*height (={}) but the given String has a length of {}.", width*height, string.chars().count());
         let vec: Vec<Pixel> = string
@@ -90,6 +225,20 @@ impl Screen {
         Screen::from_vec(vec, width, height)
     }
 
+    /// Creates a new Screen object from the grapheme of each cell and the Pixel giving its colors and style,
+    /// see [from_vec](#method.from_vec)
+    pub(crate) fn from_graphemes(cells: Vec<(String, Pixel)>, width: u32, height: u32) -> Screen {
+        let mut clusters = Clusters::default();
+        let mut vec = Vec::with_capacity(cells.len());
+        for (grapheme, mut pixel) in cells {
+            pixel.chr = clusters.chr(&grapheme, &vec);
+            vec.push(pixel);
+        }
+        let mut screen = Screen::from_vec(vec, width, height);
+        screen.clusters = clusters;
+        screen
+    }
+
     /// Get the screen width
     pub fn get_width(&self) -> u32 {
         self.width
@@ -105,9 +254,40 @@ impl Screen {
     }
 
     /// Fill the entire screen to the given pixel
+    ///
+    /// A wide character is repeated every two cells, the last column is left blank if it can't fit there
     pub fn fill(&mut self, pixel: Pixel) {
         self.empty = pixel.chr == '\u{0}';
         self.screen = vec![pixel; (self.width * self.height) as usize];
+        self.cell_links = vec![None; self.screen.len()];
+        if pixel.is_wide() {
+            for (index, cell) in self.screen.iter_mut().enumerate() {
+                if index % self.width as usize % 2 == 1 {
+                    cell.chr = pixel::CONTINUATION;
+                }
+            }
+            self.fit_wide_characters();
+        }
+    }
+
+    /// Blanks the halves of wide characters that lost their other half,
+    /// e.g. a wide character cut by the right border
+    fn fit_wide_characters(&mut self) {
+        if self.width == 0 {
+            return;
+        }
+        let width = self.width as usize;
+        for row in self.screen.chunks_mut(width) {
+            for x in 0..width {
+                if row[x].is_continuation() {
+                    if x == 0 || !row[x - 1].is_wide() {
+                        row[x].chr = ' ';
+                    }
+                } else if row[x].is_wide() && (x + 1 == width || !row[x + 1].is_continuation()) {
+                    row[x].chr = ' ';
+                }
+            }
+        }
     }
 
     /// checks whenever the screen is full of "zero" characters
@@ -143,6 +323,8 @@ impl Screen {
     /// prints a string at the specified coordinates with the specified foreground and background color  
     /// The string will be cropped if it reach the right border
     ///
+    /// Wide characters (e.g. CJK characters, most emojis) take two cells, see [pixel::CONTINUATION]
+    ///
     /// usage:
     /// ```
     /// use console_engine::Color;
@@ -151,6 +333,48 @@ impl Screen {
     /// screen.print(0, 0, "Hello, world!", Color::Blue, Color::White);
     /// ```
     pub fn print_fbg(&mut self, x: i32, y: i32, string: &str, fg: Color, bg: Color) {
+        self.print_pixels(x, y, string, fg, bg, None)
+    }
+
+    /// prints a clickable hyperlink at the specified coordinates, displaying the given text
+    ///
+    /// Terminals supporting OSC 8 hyperlinks open the target when the text is clicked,
+    /// other terminals display the text as usual.
+    ///
+    /// usage:
+    /// ```
+    /// screen.print_link(0, 0, "documentation", "https://docs.rs/console_engine");
+    /// ```
+    pub fn print_link(&mut self, x: i32, y: i32, string: &str, link: &str) {
+        self.print_link_fbg(x, y, string, link, Color::Reset, Color::Reset)
+    }
+
+    /// prints a clickable hyperlink at the specified coordinates with the specified foreground and background color
+    ///
+    /// see [print_link](#method.print_link)
+    pub fn print_link_fbg(
+        &mut self,
+        x: i32,
+        y: i32,
+        string: &str,
+        link: &str,
+        fg: Color,
+        bg: Color,
+    ) {
+        self.print_pixels(x, y, string, fg, bg, Some(link))
+    }
+
+    /// prints a string with the given colors and hyperlink target, see [print_fbg](#method.print_fbg)
+    fn print_pixels(
+        &mut self,
+        x: i32,
+        y: i32,
+        string: &str,
+        fg: Color,
+        bg: Color,
+        link: Option<&str>,
+    ) {
+        let link = link.map(|target| self.links.id(target, &self.cell_links));
         if x < self.width as i32 && y < self.height as i32 {
             let mut string = string;
             let mut y = y;
@@ -174,14 +398,14 @@ impl Screen {
             // get screen index, initializes a counter
             let mut pos = self.coord_to_index(std::cmp::max(0, x), y);
             let delta_x = if x < 0 { -x } else { 0 };
-            // set an ignore count to skip a certain number of chars if the text is hidden on the left
+            // set an ignore count to skip a certain number of columns if the text is hidden on the left
             let mut ignore_count = delta_x;
             let mut origin_row = pos / self.get_width() as usize;
-            // place each characters one by one. Stops before overflowing
-            for str_chr in string.chars() {
-                let mut chr = str_chr;
-                // process carret return and new line characters
-                if chr == '\n' {
+            // place each grapheme one by one. Stops before overflowing
+            for str_grapheme in string.graphemes(true) {
+                let mut grapheme = str_grapheme;
+                // process carret return and new line characters ("\r\n" is a single grapheme)
+                if grapheme.contains('\n') {
                     y += 1;
                     origin_row += 1;
                     ignore_count = delta_x;
@@ -189,26 +413,41 @@ impl Screen {
                         break;
                     }
                 }
-                if chr == '\n' || chr == '\r' {
+                if grapheme.contains('\n') || grapheme == "\r" {
                     // the cursor is sent back to the x index
                     // instead of rolling back on the left of the screen
                     pos = self.coord_to_index(std::cmp::max(0, x), y);
                     continue;
                 }
                 // tabs are ignored, replaced by space
-                if chr == '\t' {
-                    chr = ' ';
+                if grapheme == "\t" {
+                    grapheme = " ";
+                }
+                let mut grapheme_width = pixel::grapheme_width(grapheme);
+                // zero-width graphemes (e.g. control characters) can't be stored in a cell of their own
+                if grapheme_width == 0 {
+                    continue;
                 }
 
-                if ignore_count <= 0 {
-                    // write on the screen until the row changes,
-                    // skip the rest until a \n character is found
-                    if origin_row == pos / self.get_width() as usize {
-                        self.screen[pos] = pixel::pxl(chr, Some(fg), Some(bg), None);
-                        pos += 1;
+                if ignore_count > 0 {
+                    ignore_count -= grapheme_width as i32;
+                    if ignore_count >= 0 {
+                        continue;
                     }
-                } else {
-                    ignore_count -= 1;
+                    // a wide character is cut by the left border, only its (blank) right half is visible
+                    grapheme = " ";
+                    grapheme_width = 1;
+                    ignore_count = 0;
+                }
+                // write on the screen until the row changes,
+                // skip the rest until a \n character is found
+                // wide characters that don't fit on the right are clipped by set_pxl
+                if origin_row == pos / self.get_width() as usize {
+                    let width = self.get_width() as usize;
+                    let chr = self.clusters.chr(grapheme, &self.screen);
+                    let pixel = pixel::pxl(chr, Some(fg), Some(bg), None);
+                    self.set_linked_pxl((pos % width) as i32, (pos / width) as i32, pixel, link);
+                    pos += grapheme_width;
                 }
             }
         }
@@ -234,7 +473,9 @@ impl Screen {
         for j in 0..source.get_height() as i32 {
             for i in 0..source.get_width() as i32 {
                 // unwrap here because we are sure that we won't get out of range
-                self.set_pxl(x + i, y + j, source.get_pxl(i, j).unwrap());
+                let pxl = self.copy_cluster(source.get_pxl(i, j).unwrap(), source);
+                let link = self.copy_link(source, i, j);
+                self.set_linked_pxl(x + i, y + j, pxl, link);
             }
         }
     }
@@ -249,7 +490,9 @@ impl Screen {
                 // unwrap here because we are sure that we won't get out of range
                 let pxl = source.get_pxl(i, j).unwrap();
                 if pxl.chr != alpha_character {
-                    self.set_pxl(x + i, y + j, pxl);
+                    let pxl = self.copy_cluster(pxl, source);
+                    let link = self.copy_link(source, i, j);
+                    self.set_linked_pxl(x + i, y + j, pxl, link);
                 }
             }
         }
@@ -670,11 +913,13 @@ impl Screen {
                     for i in h_scroll..width {
                         let index = self.coord_to_index(i, j);
                         self.screen[index - step] = self.screen[index];
+                        self.cell_links[index - step] = self.cell_links[index];
                     }
                     // fill the gap with background
                     for i in (width - h_scroll)..width {
                         let index = self.coord_to_index(i, j);
                         self.screen[index] = background;
+                        self.cell_links[index] = None;
                     }
                 }
             } else {
@@ -685,11 +930,13 @@ impl Screen {
                     for i in (0..(width - h_scroll.abs())).rev() {
                         let index = self.coord_to_index(i, j);
                         self.screen[index + step] = self.screen[index];
+                        self.cell_links[index + step] = self.cell_links[index];
                     }
                     // fill the gap with background
                     for i in 0..h_scroll.abs() {
                         let index = self.coord_to_index(i, j);
                         self.screen[index] = background;
+                        self.cell_links[index] = None;
                     }
                 }
             }
@@ -706,11 +953,13 @@ impl Screen {
                     for j in v_scroll..height {
                         let index = self.coord_to_index(i, j);
                         self.screen[index - step] = self.screen[index];
+                        self.cell_links[index - step] = self.cell_links[index];
                     }
                     // fill the gap with background
                     for j in (height - v_scroll)..height {
                         let index = self.coord_to_index(i, j);
                         self.screen[index] = background;
+                        self.cell_links[index] = None;
                     }
                 }
             } else {
@@ -721,11 +970,13 @@ impl Screen {
                     for j in (0..(height - v_scroll.abs())).rev() {
                         let index = self.coord_to_index(i, j);
                         self.screen[index + step] = self.screen[index];
+                        self.cell_links[index + step] = self.cell_links[index];
                     }
                     // fill the gap with background
                     for j in 0..v_scroll.abs() {
                         let index = self.coord_to_index(i, j);
                         self.screen[index] = background;
+                        self.cell_links[index] = None;
                     }
                 }
             }
@@ -735,6 +986,10 @@ impl Screen {
     /// sets the provided character in the specified coordinates
     /// out of bounds pixels will be ignored
     ///
+    /// Setting a wide character also sets its [continuation](../pixel/constant.CONTINUATION.html) on the right,
+    /// a wide character on the last column is replaced by a space.
+    /// Overwriting either half of a wide character blanks its other half.
+    ///
     /// usage:
     /// ```
     /// use console_engine::pixel;
@@ -742,9 +997,48 @@ impl Screen {
     /// screen.set_pxl(3,8,pixel::pixel('o'));
     /// ```
     pub fn set_pxl(&mut self, x: i32, y: i32, character: Pixel) {
+        self.set_linked_pxl(x, y, character, None)
+    }
+
+    /// sets the provided character in the specified coordinates, along with the hyperlink of its cells
+    fn set_linked_pxl(&mut self, x: i32, y: i32, character: Pixel, link: Option<LinkId>) {
         if x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32 {
             let index = self.coord_to_index(x, y);
+            let has_right = x + 1 < self.width as i32;
+            let mut character = character;
+            if character.is_continuation() {
+                // a continuation is only valid on the right of a wide character
+                if x == 0 || !self.screen[index - 1].is_wide() {
+                    character.chr = ' ';
+                }
+                self.screen[index] = character;
+                self.cell_links[index] = link;
+                return;
+            }
+            // overwriting half of a wide character leaves the other half blank
+            if self.screen[index].is_continuation() && x > 0 && self.screen[index - 1].is_wide() {
+                self.screen[index - 1].chr = ' ';
+            }
+            if has_right && self.screen[index + 1].is_continuation() {
+                self.screen[index + 1].chr = ' ';
+            }
+            if character.is_wide() {
+                if has_right {
+                    // the character on the right gets covered
+                    if x + 2 < self.width as i32 && self.screen[index + 2].is_continuation() {
+                        self.screen[index + 2].chr = ' ';
+                    }
+                    let mut continuation = character;
+                    continuation.chr = pixel::CONTINUATION;
+                    self.screen[index + 1] = continuation;
+                    self.cell_links[index + 1] = link;
+                } else {
+                    // clipped by the right border
+                    character.chr = ' ';
+                }
+            }
             self.screen[index] = character;
+            self.cell_links[index] = link;
         }
     }
 
@@ -769,6 +1063,131 @@ impl Screen {
         ))
     }
 
+    /// sets a grapheme in the specified coordinates, with the colors and style of the given Pixel
+    /// out of bounds pixels will be ignored
+    ///
+    /// Only the first grapheme of the string is used, an empty string gives a space.
+    /// Graphemes made of several characters (flags, emojis with skin tones, letters with combining accents...)
+    /// are kept by the screen, the Pixel referring to them, see [Pixel](crate::pixel::Pixel).
+    ///
+    /// usage:
+    /// ```
+    /// use console_engine::pixel;
+    /// // ...
+    /// screen.set_grapheme(0, 0, "🇫🇷", pixel::pxl_plain(' '));
+    /// ```
+    pub fn set_grapheme(&mut self, x: i32, y: i32, grapheme: &str, pixel: Pixel) {
+        if x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32 {
+            let mut pixel = pixel;
+            pixel.chr = self.clusters.chr(grapheme, &self.screen);
+            self.set_pxl(x, y, pixel);
+        }
+    }
+
+    /// Get the grapheme displayed at provided coordinates, be it a single character or a grapheme cluster
+    ///
+    /// usage:
+    /// ```
+    /// screen.print(0, 0, "🇫🇷");
+    /// assert_eq!(screen.get_grapheme(0, 0).unwrap(), "🇫🇷");
+    /// ```
+    pub fn get_grapheme(&self, x: i32, y: i32) -> Result<String, String> {
+        let pixel = self.get_pxl(x, y)?;
+        let mut buffer = [0; 4];
+        Ok(String::from(self.encode_grapheme(pixel.chr, &mut buffer)))
+    }
+
+    /// Get the grapheme cluster a character of a Pixel refers to, if any
+    pub(crate) fn cluster(&self, chr: char) -> Option<&str> {
+        self.clusters.get(chr)
+    }
+
+    /// Encodes the grapheme a character of a Pixel stands for as UTF-8, similarly to [char::encode_utf8]
+    ///
+    /// The buffer is only used for single characters, grapheme clusters are returned directly
+    pub(crate) fn encode_grapheme<'a>(&'a self, chr: char, buffer: &'a mut [u8; 4]) -> &'a str {
+        match self.clusters.get(chr) {
+            Some(grapheme) => grapheme,
+            None => chr.encode_utf8(buffer),
+        }
+    }
+
+    /// Registers the grapheme cluster of a Pixel coming from another screen, returning the Pixel referring to it
+    fn copy_cluster(&mut self, pixel: Pixel, source: &Screen) -> Pixel {
+        let mut pixel = pixel;
+        if let Some(grapheme) = source.clusters.get(pixel.chr) {
+            pixel.chr = self.clusters.chr(grapheme, &self.screen);
+        }
+        pixel
+    }
+
+    /// Get the hyperlink target of the cell at provided coordinates, if any
+    ///
+    /// Links belong to the cells of the screen rather than to the pixels: [set_pxl](#method.set_pxl) removes
+    /// the link of the cells it sets, while [print_screen](#method.print_screen) copies links along with pixels.
+    ///
+    /// usage:
+    /// ```
+    /// screen.print_link(0, 0, "docs", "https://docs.rs/console_engine");
+    /// assert_eq!(screen.get_link(0, 0), Some("https://docs.rs/console_engine"));
+    /// ```
+    pub fn get_link(&self, x: i32, y: i32) -> Option<&str> {
+        self.links.get(self.link_id(x, y)?)
+    }
+
+    /// Get the identifier of the hyperlink of the cell at provided coordinates
+    pub(crate) fn link_id(&self, x: i32, y: i32) -> Option<LinkId> {
+        if x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32 {
+            return self.cell_links[self.coord_to_index(x, y)];
+        }
+        None
+    }
+
+    /// Attaches a hyperlink target to the cell at provided coordinates, or removes it with `None`
+    ///
+    /// The right half of a wide character has its own cell, see [CONTINUATION](crate::pixel::CONTINUATION).
+    /// To print linked text, see [print_link](#method.print_link).
+    pub fn set_link(&mut self, x: i32, y: i32, link: Option<&str>) {
+        if x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32 {
+            let link = link.map(|target| self.links.id(target, &self.cell_links));
+            let index = self.coord_to_index(x, y);
+            self.cell_links[index] = link;
+        }
+    }
+
+    /// Registers the hyperlink target of a cell of another screen, returning its identifier
+    fn copy_link(&mut self, source: &Screen, x: i32, y: i32) -> Option<LinkId> {
+        let id = source.link_id(x, y)?;
+        let target = source.links.get(id)?;
+        self.links.insert(id, target, &self.cell_links);
+        Some(id)
+    }
+
+    /// Get the characters of a row as a String
+    ///
+    /// The right half of wide characters is skipped, so the String reads as it is displayed
+    ///
+    /// usage:
+    /// ```
+    /// screen.print(0, 0, "Hello");
+    /// assert_eq!(screen.get_line(0).unwrap(), "Hello     ");
+    /// ```
+    pub fn get_line(&self, y: i32) -> Result<String, String> {
+        if y >= 0 && y < self.height as i32 {
+            let start = self.coord_to_index(0, y);
+            return Ok(self.screen[start..start + self.width as usize]
+                .iter()
+                .filter(|pxl| !pxl.is_continuation())
+                .map(|pxl| String::from(self.encode_grapheme(pxl.chr, &mut [0; 4])))
+                .collect());
+        }
+        Err(format!(
+            "Attempted to get_line out of bounds (row: {}, bounds: [0, {}])",
+            y,
+            self.height as i32 - 1
+        ))
+    }
+
     /// Resizes the screen to match the given width and height
     /// truncates the bottom and right side of the screen
     ///
@@ -779,18 +1198,24 @@ impl Screen {
     pub fn resize(&mut self, new_width: u32, new_height: u32) {
         // create new screens Vec
         let mut new_screen = vec![pixel::pxl_plain(' '); (new_width * new_height) as usize];
+        let mut new_links = vec![None; new_screen.len()];
         // transfer old screens into new screens
         for j in 0..std::cmp::min(self.height, new_height) {
             for i in 0..std::cmp::min(self.width, new_width) {
                 if i < self.width && j < self.height {
                     new_screen[((j * new_width) + i) as usize] =
                         self.screen[((j * self.width) + i) as usize];
+                    new_links[((j * new_width) + i) as usize] =
+                        self.cell_links[((j * self.width) + i) as usize];
                 }
             }
         }
         self.screen = new_screen;
+        self.cell_links = new_links;
         self.width = new_width;
         self.height = new_height;
+        // wide characters may now end on the right border
+        self.fit_wide_characters();
     }
 
     /// Extracts part of the current screen as a separate Screen object
@@ -815,6 +1240,7 @@ impl Screen {
         let target_width = (end_x - start_x).unsigned_abs() + 1;
         let target_height = (end_y - start_y).unsigned_abs() + 1;
         let mut extracted_screen = vec![default; (target_width * target_height) as usize];
+        let mut extracted_links = vec![None; extracted_screen.len()];
         let x_reversed = start_x > end_x;
         let y_reversed = start_y > end_y;
         let mut x = if x_reversed {
@@ -840,6 +1266,8 @@ impl Screen {
                 if i >= 0 && i < self.width as i32 && j >= 0 && j < self.height as i32 {
                     extracted_screen[((y * target_width as i32) + x) as usize] =
                         self.screen[self.coord_to_index(i, j)];
+                    extracted_links[((y * target_width as i32) + x) as usize] =
+                        self.cell_links[self.coord_to_index(i, j)];
                 }
                 x += if x_reversed { -1 } else { 1 };
             }
@@ -850,7 +1278,11 @@ impl Screen {
                 0
             };
         }
-        Screen::from_vec(extracted_screen, target_width, target_height)
+        let mut screen = Screen::from_vec(extracted_screen, target_width, target_height);
+        screen.cell_links = extracted_links;
+        screen.links = self.links.clone();
+        screen.clusters = self.clusters.clone();
+        screen
     }
 
     /// Draws the screen into the terminal
@@ -858,50 +1290,116 @@ impl Screen {
     ///
     /// You should not use this function while a ConsoleEngine is running.
     /// You may want to use ConsoleEngine's `print_screen`, `print_screen_alpha` or `set_spreen` instead
+    ///
+    /// Panics if stdout can't be written, see [try_draw](#method.try_draw) to handle the error instead
     pub fn draw(&self) {
-        let mut output = std::io::stdout();
-        crossterm::terminal::enable_raw_mode().unwrap();
+        self.try_draw().unwrap()
+    }
+
+    /// Draws the screen into the terminal, returning any error that occured while writing it
+    ///
+    /// see [draw](#method.draw) for more information
+    pub fn try_draw(&self) -> Result<(), std::io::Error> {
+        let mut output = std::io::stdout().lock();
+        // CR is needed in case the terminal is in raw mode
+        self.write_pixels(&mut output, "\r\n")?;
+        output.flush()
+    }
+
+    /// Writes the screen as a stream of ANSI escape sequences into the given output
+    ///
+    /// Lines are separated with `\n`, and attributes are reset at the end so they don't leak into what comes next.
+    /// Nothing is flushed, so you can write multiple screens before sending them.
+    ///
+    /// usage:
+    /// ```
+    /// use console_engine::screen::Screen;
+    ///
+    /// let mut scr = Screen::new(10, 2);
+    /// scr.print(0, 0, "Hello");
+    /// let mut log = std::fs::File::create("screen.log").unwrap();
+    /// scr.render_to(&mut log).unwrap();
+    /// ```
+    pub fn render_to<W: Write>(&self, output: &mut W) -> Result<(), std::io::Error> {
+        self.write_pixels(output, "\n")
+    }
+
+    /// Renders the screen into a String of ANSI escape sequences
+    ///
+    /// see [render_to](#method.render_to) for more information
+    pub fn render_to_string(&self) -> String {
+        let mut output = Vec::new();
+        // writing into a Vec never fails
+        self.render_to(&mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn write_pixels<W: Write>(
+        &self,
+        output: &mut W,
+        line_ending: &str,
+    ) -> Result<(), std::io::Error> {
+        // start from a known state, then only write what changes between two characters
+        // (SGR 0 resets the colors as well as the attributes)
+        queue!(output, style::SetAttribute(style::Attribute::Reset))?;
+        let mut current_style = pixel::Style::default();
+        let mut current_colors = (Color::Reset, Color::Reset);
+        let mut current_link = None;
         let mut skip_next = false;
         for i in 0..self.width * self.height {
             let pixel = &self.screen[i as usize];
+            let link = self.cell_links[i as usize];
             if skip_next {
                 skip_next = false;
-                continue;
-            }
-            if let Some(char_width) = unicode_width::UnicodeWidthChar::width(pixel.chr) {
-                if char_width > 1 {
-                    skip_next = true;
+            } else {
+                // the next cell is covered by the right half of a wide character
+                skip_next = pixel.is_wide();
+                for attribute in pixel.style.attributes_from(&current_style) {
+                    queue!(output, style::SetAttribute(attribute))?;
+                }
+                if pixel.style.underline_color != current_style.underline_color {
+                    queue!(
+                        output,
+                        style::SetUnderlineColor(
+                            pixel.style.underline_color.unwrap_or(Color::Reset)
+                        )
+                    )?;
+                }
+                current_style = pixel.style;
+                if pixel.fg != current_colors.0 {
+                    queue!(output, style::SetForegroundColor(pixel.fg))?;
+                }
+                if pixel.bg != current_colors.1 {
+                    queue!(output, style::SetBackgroundColor(pixel.bg))?;
                 }
+                current_colors = pixel.get_colors();
+                if link != current_link {
+                    current_link = link;
+                    let target = current_link.and_then(|id| self.links.get(id));
+                    queue!(output, style::Print(osc8(target)))?;
+                }
+                // the right half of a wide character that has been overwritten
+                let mut buffer = [0; 4];
+                let grapheme = if pixel.is_continuation() {
+                    " "
+                } else {
+                    self.encode_grapheme(pixel.chr, &mut buffer)
+                };
+                queue!(output, style::Print(grapheme))?;
             }
 
-            if pixel.style.bold { 
-                queue!(output, style::SetAttribute(style::Attribute::Bold)).unwrap(); 
-            } else {
-                queue!(output, style::SetAttribute(style::Attribute::NoBold)).unwrap();
-            }
-            if pixel.style.italic { 
-                queue!(output, style::SetAttribute(style::Attribute::Italic)).unwrap(); 
-            } else {
-                queue!(output, style::SetAttribute(style::Attribute::NoItalic)).unwrap();
-            }
-            if pixel.style.underlined { 
-                queue!(output, style::SetAttribute(style::Attribute::Underlined)).unwrap(); 
-            } else {
-                queue!(output, style::SetAttribute(style::Attribute::NoUnderline)).unwrap();
-            }
-            queue!(
-                output,
-                style::SetForegroundColor(pixel.fg),
-                style::SetBackgroundColor(pixel.bg),
-                style::Print(pixel.chr),
-            ).unwrap();
-
-            if i != self.width * self.height - 1 && i % self.width == self.width - 1 {
-                queue!(output, style::Print("\r\n")).unwrap();
+            if i % self.width == self.width - 1 {
+                // a wide character never covers the first cell of the next row
+                skip_next = false;
+                if i != self.width * self.height - 1 {
+                    queue!(output, style::Print(line_ending))?;
+                }
             }
-            output.flush().unwrap();
         }
-        crossterm::terminal::disable_raw_mode().unwrap();
+        if current_link.is_some() {
+            queue!(output, style::Print(osc8(None)))?;
+        }
+        queue!(output, style::SetAttribute(style::Attribute::Reset))
     }
 
     /// Converts x and y coordinates to screen index
@@ -912,3 +1410,223 @@ impl Screen {
         ((y * self.width as i32) + x) as usize
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Screen;
+    use crate::pixel::{self, Style};
+    use crate::Color;
+
+    #[test]
+    fn render_to_string() {
+        let mut scr = Screen::new(3, 2);
+        scr.print(0, 0, "ab");
+        scr.set_pxl(
+            0,
+            1,
+            pixel::pxl(
+                'c',
+                Some(Color::Red),
+                None,
+                Some(Style {
+                    bold: true,
+                    ..Style::default()
+                }),
+            ),
+        );
+        scr.print(1, 1, "d");
+        assert_eq!(
+            scr.render_to_string(),
+            concat!(
+                "\x1b[0m",
+                "ab \n",
+                "\x1b[1m\x1b[38;5;9mc",
+                "\x1b[22m\x1b[39md ",
+                "\x1b[0m"
+            )
+        );
+    }
+
+    #[test]
+    fn wide_characters_on_the_border() {
+        let mut scr = Screen::new(4, 2);
+        scr.print(0, 0, "ab界\ncdef");
+        scr.resize(3, 2);
+        assert_eq!(scr.render_to_string(), "\x1b[0mab \ncde\x1b[0m");
+
+        let scr = Screen::from_string(String::from("界x"), Color::Reset, Color::Reset, 2, 1);
+        assert_eq!(scr.render_to_string(), "\x1b[0m x\x1b[0m");
+        let scr = Screen::from_vec(
+            vec![
+                pixel::pxl_plain('界'),
+                pixel::pxl_plain(pixel::CONTINUATION),
+                pixel::pxl_plain('界'),
+                pixel::pxl_plain('y'),
+                pixel::pxl_plain(pixel::CONTINUATION),
+                pixel::pxl_plain('界'),
+            ],
+            3,
+            2,
+        );
+        assert_eq!(scr.render_to_string(), "\x1b[0m界 \ny  \x1b[0m");
+
+        let mut scr = Screen::new(5, 2);
+        scr.fill(pixel::pxl_plain('界'));
+        assert_eq!(scr.render_to_string(), "\x1b[0m界界 \n界界 \x1b[0m");
+    }
+
+    #[test]
+    fn links() {
+        let mut scr = Screen::new(5, 2);
+        scr.print_link(1, 0, "ab\nc", "https://example.com");
+        scr.print_link(2, 0, "字", "file:///tmp");
+        let link = |x, y| scr.get_link(x, y);
+        assert_eq!(link(0, 0), None);
+        assert_eq!(link(1, 0), Some("https://example.com"));
+        assert_eq!(link(1, 1), Some("https://example.com"));
+        // the right half of the wide character belongs to the same link
+        assert_eq!(link(3, 0), Some("file:///tmp"));
+        assert_eq!(link(4, 0), None);
+
+        // links follow the pixels copied to other screens
+        let mut other = Screen::new(3, 1);
+        other.print_screen(-1, 0, &scr);
+        assert_eq!(other.get_link(0, 0), Some("https://example.com"));
+        assert_eq!(other.get_link(1, 0), Some("file:///tmp"));
+        let extracted = scr.extract(1, 1, 2, 1, pixel::pxl_plain(' '));
+        assert_eq!(extracted.get_link(0, 0), Some("https://example.com"));
+        assert_eq!(extracted.get_link(1, 0), None);
+        // but not pixels set directly
+        let mut other = Screen::new(1, 1);
+        other.set_pxl(0, 0, scr.get_pxl(1, 0).unwrap());
+        assert_eq!(other.get_link(0, 0), None);
+        let mut linked = scr.clone();
+        linked.set_pxl(1, 0, scr.get_pxl(1, 0).unwrap());
+        assert_eq!(linked.get_link(1, 0), None);
+        // links move with scrolled pixels
+        linked.scroll(1, 0, pixel::pxl_plain(' '));
+        assert_eq!(linked.get_link(1, 0), Some("file:///tmp"));
+        assert_eq!(linked.get_link(4, 0), None);
+        other.set_link(0, 0, Some("https://example.org"));
+        assert_eq!(other.get_link(0, 0), Some("https://example.org"));
+
+        // targets that are no longer used are dropped
+        let mut scr = Screen::new(2, 1);
+        for i in 0..100 {
+            scr.print_link(0, 0, "a", &format!("https://example.com/{}", i));
+        }
+        scr.print_link(1, 0, "b", "https://example.com/0");
+        assert!(scr.links.targets.len() <= super::Links::MIN_LIMIT);
+        assert_eq!(scr.get_link(0, 0), Some("https://example.com/99"));
+        assert_eq!(scr.get_link(1, 0), Some("https://example.com/0"));
+
+        let mut scr = Screen::new(3, 1);
+        scr.print_link(0, 0, "ab", "https://example.com");
+        assert_eq!(
+            scr.render_to_string(),
+            concat!(
+                "\x1b[0m",
+                "\x1b]8;;https://example.com\x1b\\ab",
+                "\x1b]8;;\x1b\\ ",
+                "\x1b[0m"
+            )
+        );
+    }
+
+    #[test]
+    fn wide_characters() {
+        let mut scr = Screen::new(6, 3);
+        scr.print_fbg(0, 0, "a界b", Color::Red, Color::Reset);
+        assert_eq!(scr.get_line(0).unwrap(), "a界b  ");
+        assert_eq!(scr.get_pxl(2, 0).unwrap().chr, pixel::CONTINUATION);
+        assert_eq!(scr.get_pxl(2, 0).unwrap().fg, Color::Red);
+        assert_eq!(scr.get_pxl(3, 0).unwrap().chr, 'b');
+
+        // clipped by the right border
+        scr.print(3, 1, "ab界");
+        assert_eq!(scr.get_line(1).unwrap(), "   ab ");
+        // cut by the left border
+        scr.print(-1, 2, "界x");
+        assert_eq!(scr.get_line(2).unwrap(), " x    ");
+
+        // overwriting either half blanks the other one
+        scr.print(0, 0, "界界");
+        scr.set_pxl(1, 0, pixel::pxl_plain('x'));
+        assert_eq!(scr.get_line(0).unwrap(), " x界  ");
+        scr.set_pxl(2, 0, pixel::pxl_plain('y'));
+        assert_eq!(scr.get_line(0).unwrap(), " xy   ");
+        assert!(!scr.get_pxl(3, 0).unwrap().is_continuation());
+
+        // a wide character covering the left half of another one
+        scr.print(0, 1, " 界");
+        scr.set_pxl(0, 1, pixel::pxl_plain('字'));
+        assert_eq!(scr.get_line(1).unwrap(), "字 ab ");
+
+        // copying a screen keeps wide characters together
+        let mut copy = Screen::new(6, 3);
+        copy.print_screen(0, 0, &scr);
+        assert_eq!(copy.get_line(1).unwrap(), "字 ab ");
+        copy.print_screen(-1, 0, &scr);
+        assert_eq!(copy.get_line(1).unwrap(), "  ab  ");
+
+        assert_eq!(
+            scr.render_to_string(),
+            "\x1b[0m xy   \n字 ab \n x    \x1b[0m"
+        );
+    }
+
+    #[test]
+    fn graphemes() {
+        let mut scr = Screen::new(6, 2);
+        scr.print(0, 0, "e\u{301}🇫🇷👍🏽\r\nx");
+        assert_eq!(scr.get_line(0).unwrap(), "e\u{301}🇫🇷👍🏽 ");
+        assert_eq!(scr.get_line(1).unwrap(), "x     ");
+
+        let accent = scr.get_pxl(0, 0).unwrap();
+        assert!(accent.is_cluster());
+        assert!(!accent.is_wide());
+        assert_eq!(scr.get_grapheme(0, 0).unwrap(), "e\u{301}");
+        assert!(scr.get_pxl(1, 0).unwrap().is_wide());
+        assert!(scr.get_pxl(2, 0).unwrap().is_continuation());
+        assert_eq!(scr.get_grapheme(1, 0).unwrap(), "🇫🇷");
+        // a cluster is only stored once
+        let mut other = scr.clone();
+        other.set_grapheme(5, 1, "e\u{301}", pixel::pxl_plain(' '));
+        assert_eq!(other.get_pxl(5, 1).unwrap(), accent);
+
+        // long emoji sequences are kept whole
+        let family = "👨🏽\u{200D}👩🏽\u{200D}👧🏽\u{200D}👦🏽";
+        other.set_grapheme(0, 1, family, pixel::pxl_plain(' '));
+        assert_eq!(other.get_grapheme(0, 1).unwrap(), family);
+        assert!(other.get_pxl(0, 1).unwrap().is_wide());
+        // characters used to refer to clusters can still be printed
+        other.print(2, 1, "\u{100000}");
+        assert_eq!(other.get_grapheme(2, 1).unwrap(), "\u{100000}");
+
+        // clusters follow the pixels copied to other screens
+        let mut copy = Screen::new(5, 1);
+        copy.print(0, 0, "a\u{308}");
+        copy.print_screen(-1, 0, &other);
+        assert_eq!(copy.get_line(0).unwrap(), "🇫🇷👍🏽 ");
+        let extracted = other.extract(0, 1, 3, 1, pixel::pxl_plain(' '));
+        assert_eq!(
+            extracted.get_line(0).unwrap(),
+            family.to_owned() + "\u{100000} "
+        );
+
+        // clusters that are no longer used are dropped
+        let mut swept = Screen::new(2, 1);
+        swept.print(1, 0, "o\u{308}");
+        for i in 0..1000u32 {
+            let mark = |code| char::from_u32(0x300 + code).unwrap();
+            swept.print(0, 0, &format!("a{}{}", mark(i % 0x70), mark(i / 0x70)));
+        }
+        assert!(swept.clusters.graphemes.len() <= super::Clusters::MIN_LIMIT);
+        assert_eq!(swept.get_line(0).unwrap(), "a\u{367}\u{308}o\u{308}");
+
+        assert_eq!(
+            scr.render_to_string(),
+            "\x1b[0me\u{301}🇫🇷👍🏽 \nx     \x1b[0m"
+        );
+    }
+}
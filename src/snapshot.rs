@@ -0,0 +1,814 @@
+//! Text snapshots of screens
+//!
+//! A snapshot is a stable, human-readable text representation of a [Screen],
+//! meant to be stored in golden files and compared in tests.
+//!
+//! ```text
+//! screen 6x2
+//! |Hello |
+//! |World!|
+//! styles
+//! |AAAAAA|
+//! |BBBBBC|
+//! legend
+//! A Reset Reset -
+//! B Red #102030 bi
+//! C @208 Reset cr Blue
+//! D Reset Reset u link https://docs.rs
+//! ```
+//!
+//! - The `screen` section contains the grapheme of each cell, row by row between `|`.
+//!   Backslashes are written `\\` and non-printable characters are written `\u{hex}`.
+//!   Graphemes that would be merged with their neighbour are escaped as well, e.g. `\u{1f1eb 1f1f7}`.
+//!   The right half of wide characters is a [continuation](crate::pixel::CONTINUATION), written `\u{ffff}`.
+//! - The `styles` section contains, for each cell, a key referring to an entry of the `legend`.
+//! - Each `legend` entry gives the foreground color, the background color and the style of the cells using its key,
+//!   followed by the underline color if it has one, and by `link` and the hyperlink target if they have one.
+//!   Targets are escaped like graphemes, so they always fit on their line.
+//!   Colors use their name, `#rrggbb` for RGB colors or `@index` for ANSI values.
+//!   Styles are a combination of `b` (bold), `d` (dim), `i` (italic), `k` (blink), `r` (reverse), `h` (hidden),
+//!   `s` (strikethrough) and one of `u`, `w`, `c` or `o` (single, double, curly or dotted underline), or `-` for none.
+//!
+//! usage:
+//! ```no_run
+//! use console_engine::screen::Screen;
+//! use console_engine::snapshot;
+//!
+//! let mut scr = Screen::new(10, 2);
+//! scr.print(0, 0, "Hello");
+//!
+//! let expected = Screen::from_snapshot(&std::fs::read_to_string("tests/hello.snap").unwrap()).unwrap();
+//! let diff = snapshot::diff(&expected, &scr);
+//! assert!(diff.is_empty(), "{}", diff);
+//! ```
+
+use std::fmt;
+
+use crossterm::style::Color;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::pixel::{self, Pixel, Style, UnderlineStyle};
+use crate::screen::Screen;
+
+impl Screen {
+    /// Serializes the screen into a snapshot
+    ///
+    /// see the [snapshot](../snapshot/index.html) module for the format description
+    pub fn to_snapshot(&self) -> String {
+        let width = self.get_width() as i32;
+        let height = self.get_height() as i32;
+        let mut legend: Vec<(Color, Color, Style, Option<&str>)> = vec![];
+        let mut keys: Vec<char> = vec![];
+        let mut next_keys = legend_keys();
+        let mut text = String::new();
+        let mut styles = String::new();
+
+        for y in 0..height {
+            text.push('|');
+            styles.push('|');
+            let mut previous = String::new();
+            for x in 0..width {
+                let pxl = self.get_pxl(x, y).unwrap();
+                let grapheme = self.get_grapheme(x, y).unwrap();
+                write_grapheme(&mut text, &grapheme, &previous);
+                previous = grapheme;
+                let entry = (pxl.fg, pxl.bg, pxl.style, self.get_link(x, y));
+                let index = match legend.iter().position(|e| *e == entry) {
+                    Some(index) => index,
+                    None => {
+                        legend.push(entry);
+                        keys.push(next_keys.next().unwrap_or(char::REPLACEMENT_CHARACTER));
+                        legend.len() - 1
+                    }
+                };
+                styles.push(keys[index]);
+            }
+            text.push_str("|\n");
+            styles.push_str("|\n");
+        }
+
+        let mut snapshot = format!("screen {}x{}\n", width, height);
+        snapshot.push_str(&text);
+        snapshot.push_str("styles\n");
+        snapshot.push_str(&styles);
+        snapshot.push_str("legend\n");
+        for (key, (fg, bg, style, link)) in keys.iter().zip(legend.iter()) {
+            snapshot.push_str(&format!(
+                "{} {} {} {}",
+                key,
+                color_to_string(*fg),
+                color_to_string(*bg),
+                style_to_string(*style)
+            ));
+            if let Some(underline_color) = style.underline_color {
+                snapshot.push_str(&format!(" {}", color_to_string(underline_color)));
+            }
+            if let Some(link) = link {
+                snapshot.push_str(" link ");
+                write_link(&mut snapshot, link);
+            }
+            snapshot.push('\n');
+        }
+        snapshot
+    }
+
+    /// Creates a new Screen object from a snapshot
+    ///
+    /// see the [snapshot](../snapshot/index.html) module for the format description
+    pub fn from_snapshot(snapshot: &str) -> Result<Screen, String> {
+        let mut lines = snapshot.lines().enumerate();
+        let mut next_line = |expected: &str| -> Result<(usize, &str), String> {
+            lines
+                .next()
+                .ok_or(format!("Unexpected end of snapshot, expected {}", expected))
+        };
+
+        let (number, header) = next_line("the screen header")?;
+        let size = header
+            .strip_prefix("screen ")
+            .and_then(|size| size.split_once('x'))
+            .and_then(|(w, h)| Some((w.parse::<u32>().ok()?, h.parse::<u32>().ok()?)));
+        let (width, height) = match size {
+            Some(size) => size,
+            None => {
+                return Err(format!(
+                    "Line {}: invalid screen header '{}'",
+                    number + 1,
+                    header
+                ))
+            }
+        };
+
+        let mut graphemes: Vec<String> = Vec::with_capacity((width * height) as usize);
+        for _ in 0..height {
+            let (number, line) = next_line("a screen row")?;
+            let row = parse_row(line, width).map_err(|e| format!("Line {}: {}", number + 1, e))?;
+            graphemes.extend(row);
+        }
+
+        let (number, line) = next_line("the styles section")?;
+        if line != "styles" {
+            return Err(format!(
+                "Line {}: expected 'styles', found '{}'",
+                number + 1,
+                line
+            ));
+        }
+        let mut keys: Vec<char> = Vec::with_capacity((width * height) as usize);
+        for _ in 0..height {
+            let (number, line) = next_line("a styles row")?;
+            let row = parse_row(line, width).map_err(|e| format!("Line {}: {}", number + 1, e))?;
+            keys.extend(row.iter().map(|key| key.chars().next().unwrap_or(' ')));
+        }
+
+        let (number, line) = next_line("the legend section")?;
+        if line != "legend" {
+            return Err(format!(
+                "Line {}: expected 'legend', found '{}'",
+                number + 1,
+                line
+            ));
+        }
+        let mut legend: Vec<(char, Color, Color, Style, Option<String>)> = vec![];
+        for (number, line) in lines {
+            if line.is_empty() {
+                continue;
+            }
+            legend.push(parse_legend(line).map_err(|e| format!("Line {}: {}", number + 1, e))?);
+        }
+
+        let mut cells: Vec<(String, Pixel)> = Vec::with_capacity(graphemes.len());
+        let mut links: Vec<Option<&str>> = Vec::with_capacity(graphemes.len());
+        for (grapheme, key) in graphemes.into_iter().zip(keys) {
+            let (_, fg, bg, style, link) = legend
+                .iter()
+                .find(|(k, _, _, _, _)| *k == key)
+                .ok_or(format!("Style key '{}' is missing from the legend", key))?;
+            cells.push((
+                grapheme,
+                pixel::pxl(' ', Some(*fg), Some(*bg), Some(*style)),
+            ));
+            links.push(link.as_deref());
+        }
+        let mut screen = Screen::from_graphemes(cells, width, height);
+        for (index, link) in links.into_iter().enumerate() {
+            if link.is_some() {
+                let (x, y) = (index as u32 % width, index as u32 / width);
+                screen.set_link(x as i32, y as i32, link);
+            }
+        }
+        Ok(screen)
+    }
+}
+
+/// A cell that differs between two screens
+#[derive(Debug, Clone)]
+pub struct CellDiff {
+    pub x: u32,
+    pub y: u32,
+    pub expected: Pixel,
+    pub actual: Pixel,
+    /// Graphemes of the expected and actual cells, as the pixels may refer to grapheme clusters of their screen
+    pub expected_grapheme: String,
+    pub actual_grapheme: String,
+    /// Hyperlink targets of the expected and actual cells, which aren't part of the pixels
+    pub expected_link: Option<String>,
+    pub actual_link: Option<String>,
+}
+
+/// Result of the comparison between two screens, see [diff]
+///
+/// Its Display implementation lists every difference, one per line
+#[derive(Debug, Clone)]
+pub struct ScreenDiff {
+    /// Sizes of the expected and actual screens, if they differ
+    pub size: Option<((u32, u32), (u32, u32))>,
+    /// Differing cells, in reading order
+    pub cells: Vec<CellDiff>,
+}
+
+impl ScreenDiff {
+    /// Checks whenever both screens were identical
+    pub fn is_empty(&self) -> bool {
+        self.size.is_none() && self.cells.is_empty()
+    }
+}
+
+impl fmt::Display for ScreenDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(((expected_w, expected_h), (actual_w, actual_h))) = self.size {
+            writeln!(
+                f,
+                "size: expected {}x{}, got {}x{}",
+                expected_w, expected_h, actual_w, actual_h
+            )?;
+        }
+        for cell in self.cells.iter() {
+            writeln!(
+                f,
+                "[{}, {}]: expected {}, got {}",
+                cell.x,
+                cell.y,
+                pixel_to_string(
+                    &cell.expected,
+                    &cell.expected_grapheme,
+                    cell.expected_link.as_deref()
+                ),
+                pixel_to_string(
+                    &cell.actual,
+                    &cell.actual_grapheme,
+                    cell.actual_link.as_deref()
+                )
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two screens cell by cell
+///
+/// If the screens don't have the same size, only their common area is compared.
+///
+/// usage:
+/// ```
+/// use console_engine::snapshot;
+///
+/// let diff = snapshot::diff(&expected, &engine.get_screen());
+/// assert!(diff.is_empty(), "screen mismatch:\n{}", diff);
+/// ```
+pub fn diff(expected: &Screen, actual: &Screen) -> ScreenDiff {
+    let expected_size = (expected.get_width(), expected.get_height());
+    let actual_size = (actual.get_width(), actual.get_height());
+    let mut cells = vec![];
+    for y in 0..std::cmp::min(expected_size.1, actual_size.1) {
+        for x in 0..std::cmp::min(expected_size.0, actual_size.0) {
+            let expected_pxl = expected.get_pxl(x as i32, y as i32).unwrap();
+            let actual_pxl = actual.get_pxl(x as i32, y as i32).unwrap();
+            let expected_grapheme = expected.get_grapheme(x as i32, y as i32).unwrap();
+            let actual_grapheme = actual.get_grapheme(x as i32, y as i32).unwrap();
+            let expected_link = expected.get_link(x as i32, y as i32);
+            let actual_link = actual.get_link(x as i32, y as i32);
+            // the characters of grapheme clusters differ between screens
+            let same_pixel = (expected_pxl.fg, expected_pxl.bg, expected_pxl.style)
+                == (actual_pxl.fg, actual_pxl.bg, actual_pxl.style)
+                && expected_grapheme == actual_grapheme;
+            if !same_pixel || expected_link != actual_link {
+                cells.push(CellDiff {
+                    x,
+                    y,
+                    expected: expected_pxl,
+                    actual: actual_pxl,
+                    expected_grapheme,
+                    actual_grapheme,
+                    expected_link: expected_link.map(String::from),
+                    actual_link: actual_link.map(String::from),
+                });
+            }
+        }
+    }
+    ScreenDiff {
+        size: if expected_size != actual_size {
+            Some((expected_size, actual_size))
+        } else {
+            None
+        },
+        cells,
+    }
+}
+
+/// Gives the keys used in the styles section, in the order of the legend entries
+///
+/// After ASCII letters and digits, keys continue from the latin-1 supplement letters with every character
+/// taking a single column that stays a grapheme of its own next to any other key.
+fn legend_keys() -> impl Iterator<Item = char> {
+    const KEYS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    let others = (0xC0..=char::MAX as u32)
+        .filter_map(char::from_u32)
+        .filter(|chr| {
+            !chr.is_control()
+                && !chr.is_whitespace()
+                && (*chr as u32 & 0xFFFE) != 0xFFFE
+                && unicode_width::UnicodeWidthChar::width(*chr) == Some(1)
+                // neither combining marks, nor prepended or paired characters
+                && format!("a{0}{0}a", chr).graphemes(true).count() == 4
+        });
+    KEYS.chars().chain(others)
+}
+
+/// Writes the grapheme of a cell, escaping it if needed
+///
+/// Graphemes are escaped when they contain backslashes, non-printable characters,
+/// or when they would be merged with the grapheme of the `previous` cell once read back.
+fn write_grapheme(output: &mut String, grapheme: &str, previous: &str) {
+    let needs_escape = grapheme
+        .chars()
+        .any(|chr| chr.is_control() || (chr as u32 & 0xFFFE) == 0xFFFE)
+        || (grapheme != "\\" && grapheme.contains('\\'))
+        || format!("{}{}", previous, grapheme).graphemes(true).count()
+            != 1 + !previous.is_empty() as usize;
+    if grapheme == "\\" {
+        output.push_str("\\\\");
+    } else if needs_escape {
+        let codes: Vec<String> = grapheme
+            .chars()
+            .map(|chr| format!("{:x}", chr as u32))
+            .collect();
+        output.push_str(&format!("\\u{{{}}}", codes.join(" ")));
+    } else {
+        output.push_str(grapheme);
+    }
+}
+
+/// Writes a hyperlink target, escaping backslashes and non-printable characters
+fn write_link(output: &mut String, target: &str) {
+    for chr in target.chars() {
+        if chr == '\\' {
+            output.push_str("\\\\");
+        } else if chr.is_control() || (chr as u32 & 0xFFFE) == 0xFFFE {
+            output.push_str(&format!("\\u{{{:x}}}", chr as u32));
+        } else {
+            output.push(chr);
+        }
+    }
+}
+
+/// Parses a hyperlink target written by [write_link]
+fn parse_link(text: &str) -> Result<String, String> {
+    let mut target = String::new();
+    let mut content = text;
+    while let Some(start) = content.find('\\') {
+        target.push_str(&content[..start]);
+        let escaped = &content[start + 1..];
+        if let Some(rest) = escaped.strip_prefix('\\') {
+            target.push('\\');
+            content = rest;
+        } else if let Some((code, rest)) = escaped
+            .strip_prefix("u{")
+            .and_then(|escape| escape.split_once('}'))
+        {
+            let chr = u32::from_str_radix(code, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or(format!("invalid escape sequence '\\u{{{}}}'", code))?;
+            target.push(chr);
+            content = rest;
+        } else {
+            return Err(format!("invalid escape sequence in link '{}'", text));
+        }
+    }
+    target.push_str(content);
+    Ok(target)
+}
+
+/// Parses a `|...|` row containing exactly `width` graphemes
+fn parse_row(line: &str, width: u32) -> Result<Vec<String>, String> {
+    let mut content = line
+        .strip_prefix('|')
+        .and_then(|line| line.strip_suffix('|'))
+        .ok_or(format!("row '{}' must be enclosed in '|'", line))?;
+    let mut row = vec![];
+    while !content.is_empty() {
+        let Some(escaped) = content.strip_prefix('\\') else {
+            let grapheme = content.graphemes(true).next().unwrap();
+            row.push(String::from(grapheme));
+            content = &content[grapheme.len()..];
+            continue;
+        };
+        if let Some(rest) = escaped.strip_prefix('\\') {
+            row.push(String::from("\\"));
+            content = rest;
+        } else if let Some((codes, rest)) = escaped
+            .strip_prefix("u{")
+            .and_then(|escape| escape.split_once('}'))
+        {
+            let grapheme = codes
+                .split(' ')
+                .map(|code| u32::from_str_radix(code, 16).ok().and_then(char::from_u32))
+                .collect::<Option<String>>()
+                .ok_or(format!("invalid escape sequence '\\u{{{}}}'", codes))?;
+            row.push(grapheme);
+            content = rest;
+        } else {
+            return Err(String::from("invalid escape sequence"));
+        }
+    }
+    if row.len() != width as usize {
+        return Err(format!(
+            "row has a length of {} but the screen has a width of {}",
+            row.len(),
+            width
+        ));
+    }
+    Ok(row)
+}
+
+fn parse_legend(line: &str) -> Result<(char, Color, Color, Style, Option<String>), String> {
+    // the hyperlink target is the rest of the line, the other parts can't contain "link"
+    let (attributes, link) = match line.split_once(" link ") {
+        Some((attributes, link)) => (attributes, Some(parse_link(link)?)),
+        None => (line, None),
+    };
+    let mut parts = attributes.split(' ');
+    let mut next_part = |name: &str| {
+        parts
+            .next()
+            .ok_or(format!("legend entry '{}' is missing its {}", line, name))
+    };
+    let mut key = next_part("key")?.chars();
+    let key = match (key.next(), key.next()) {
+        (Some(key), None) => key,
+        _ => return Err(format!("legend entry '{}' has an invalid key", line)),
+    };
+    let fg = next_part("foreground color")?;
+    let fg = color_from_str(fg).ok_or(format!("unknown color '{}'", fg))?;
+    let bg = next_part("background color")?;
+    let bg = color_from_str(bg).ok_or(format!("unknown color '{}'", bg))?;
+    let style = next_part("style")?;
+    let mut style = style_from_str(style).ok_or(format!("unknown style '{}'", style))?;
+    // the underline color is optional
+    if let Some(underline_color) = parts.next() {
+        style.underline_color = Some(
+            color_from_str(underline_color)
+                .ok_or(format!("unknown color '{}'", underline_color))?,
+        );
+    }
+    if parts.next().is_some() {
+        return Err(format!("legend entry '{}' has too many parts", line));
+    }
+    Ok((key, fg, bg, style, link))
+}
+
+fn pixel_to_string(pxl: &Pixel, grapheme: &str, link: Option<&str>) -> String {
+    let mut chr = String::new();
+    write_grapheme(&mut chr, grapheme, "");
+    let mut string = format!(
+        "'{}' (fg: {}, bg: {}, style: {}",
+        chr,
+        color_to_string(pxl.fg),
+        color_to_string(pxl.bg),
+        style_to_string(pxl.style)
+    );
+    if let Some(underline_color) = pxl.style.underline_color {
+        string.push_str(&format!(
+            ", underline: {}",
+            color_to_string(underline_color)
+        ));
+    }
+    if let Some(link) = link {
+        string.push_str(&format!(", link: {}", link));
+    }
+    string.push(')');
+    string
+}
+
+const COLOR_NAMES: [(Color, &str); 17] = [
+    (Color::Reset, "Reset"),
+    (Color::Black, "Black"),
+    (Color::DarkGrey, "DarkGrey"),
+    (Color::Red, "Red"),
+    (Color::DarkRed, "DarkRed"),
+    (Color::Green, "Green"),
+    (Color::DarkGreen, "DarkGreen"),
+    (Color::Yellow, "Yellow"),
+    (Color::DarkYellow, "DarkYellow"),
+    (Color::Blue, "Blue"),
+    (Color::DarkBlue, "DarkBlue"),
+    (Color::Magenta, "Magenta"),
+    (Color::DarkMagenta, "DarkMagenta"),
+    (Color::Cyan, "Cyan"),
+    (Color::DarkCyan, "DarkCyan"),
+    (Color::White, "White"),
+    (Color::Grey, "Grey"),
+];
+
+fn color_to_string(color: Color) -> String {
+    match color {
+        Color::Rgb { r, g, b } => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        Color::AnsiValue(value) => format!("@{}", value),
+        _ => COLOR_NAMES
+            .iter()
+            .find(|(c, _)| *c == color)
+            .map(|(_, name)| String::from(*name))
+            .unwrap_or_else(|| String::from("Reset")),
+    }
+}
+
+fn color_from_str(string: &str) -> Option<Color> {
+    if let Some(hex) = string.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        return Some(Color::Rgb {
+            r: (value >> 16) as u8,
+            g: (value >> 8) as u8,
+            b: value as u8,
+        });
+    }
+    if let Some(value) = string.strip_prefix('@') {
+        return value.parse().ok().map(Color::AnsiValue);
+    }
+    COLOR_NAMES
+        .iter()
+        .find(|(_, name)| *name == string)
+        .map(|(color, _)| *color)
+}
+
+/// Flags of the underline styles, used when the style is underlined
+const UNDERLINE_FLAGS: [(UnderlineStyle, char); 4] = [
+    (UnderlineStyle::Single, 'u'),
+    (UnderlineStyle::Double, 'w'),
+    (UnderlineStyle::Curly, 'c'),
+    (UnderlineStyle::Dotted, 'o'),
+];
+
+fn style_to_string(style: Style) -> String {
+    let mut string = String::new();
+    let underline = UNDERLINE_FLAGS
+        .iter()
+        .find(|(underline_style, _)| *underline_style == style.underline_style)
+        .map_or('u', |(_, flag)| *flag);
+    for (enabled, flag) in [
+        (style.bold, 'b'),
+        (style.dim, 'd'),
+        (style.italic, 'i'),
+        (style.underlined, underline),
+        (style.blink, 'k'),
+        (style.reverse, 'r'),
+        (style.hidden, 'h'),
+        (style.strikethrough, 's'),
+    ] {
+        if enabled {
+            string.push(flag);
+        }
+    }
+    if string.is_empty() {
+        string.push('-');
+    }
+    string
+}
+
+fn style_from_str(string: &str) -> Option<Style> {
+    let mut style = Style::default();
+    if string == "-" {
+        return Some(style);
+    }
+    for flag in string.chars() {
+        match flag {
+            'b' => style.bold = true,
+            'd' => style.dim = true,
+            'i' => style.italic = true,
+            'k' => style.blink = true,
+            'r' => style.reverse = true,
+            'h' => style.hidden = true,
+            's' => style.strikethrough = true,
+            _ => {
+                let (underline_style, _) = UNDERLINE_FLAGS.iter().find(|(_, f)| *f == flag)?;
+                style.underlined = true;
+                style.underline_style = *underline_style;
+            }
+        }
+    }
+    Some(style)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::{self, Style, UnderlineStyle};
+    use crate::screen::Screen;
+    use crate::Color;
+
+    #[test]
+    fn round_trip() {
+        let mut scr = Screen::new(6, 2);
+        scr.print(0, 0, "a\\b|");
+        scr.set_pxl(5, 0, pixel::pxl_plain('\u{0}'));
+        scr.print_fbg(
+            0,
+            1,
+            "World!",
+            Color::Red,
+            Color::Rgb {
+                r: 16,
+                g: 32,
+                b: 48,
+            },
+        );
+        let style = Style {
+            bold: true,
+            italic: true,
+            ..Style::default()
+        };
+        let extended = Style {
+            dim: true,
+            blink: true,
+            reverse: true,
+            hidden: true,
+            underlined: true,
+            underline_style: UnderlineStyle::Curly,
+            underline_color: Some(Color::AnsiValue(4)),
+            ..Style::default()
+        };
+        scr.set_pxl(4, 0, pixel::pxl(' ', None, None, Some(extended)));
+        scr.set_pxl(
+            5,
+            1,
+            pixel::pxl('!', Some(Color::AnsiValue(208)), None, Some(style)),
+        );
+
+        let snapshot = scr.to_snapshot();
+        assert_eq!(
+            snapshot,
+            "screen 6x2\n\
+             |a\\\\b| \\u{0}|\n\
+             |World!|\n\
+             styles\n\
+             |AAAABA|\n\
+             |CCCCCD|\n\
+             legend\n\
+             A Reset Reset -\n\
+             B Reset Reset dckrh @4\n\
+             C Red #102030 -\n\
+             D @208 Reset bi\n"
+        );
+        let parsed = Screen::from_snapshot(&snapshot).unwrap();
+        assert!(super::diff(&scr, &parsed).is_empty());
+    }
+
+    #[test]
+    fn graphemes() {
+        let mut scr = Screen::new(7, 1);
+        scr.print(0, 0, "e\u{301}🇫🇷x");
+        // cells that would be merged with their neighbour once read back
+        scr.set_pxl(4, 0, pixel::pxl_plain('\u{301}'));
+        scr.set_pxl(5, 0, pixel::pxl_plain('\u{1f1eb}'));
+        scr.set_pxl(6, 0, pixel::pxl_plain('\u{1f1f7}'));
+
+        let snapshot = scr.to_snapshot();
+        assert!(
+            snapshot.starts_with("screen 7x1\n|e\u{301}🇫🇷\\u{ffff}x\\u{301}\u{1f1eb}\\u{1f1f7}|\n")
+        );
+        let parsed = Screen::from_snapshot(&snapshot).unwrap();
+        assert!(super::diff(&scr, &parsed).is_empty());
+        assert_eq!(parsed.get_grapheme(1, 0).unwrap(), "🇫🇷");
+
+        // the same cluster may be referred to by different characters on each screen
+        let mut other = Screen::new(7, 1);
+        other.print(0, 0, "a\u{301}");
+        other.print_screen(0, 0, &scr);
+        assert_ne!(other.get_pxl(0, 0).unwrap(), scr.get_pxl(0, 0).unwrap());
+        assert!(super::diff(&scr, &other).is_empty());
+    }
+
+    #[test]
+    fn links() {
+        let mut scr = Screen::new(4, 1);
+        scr.print_link(0, 0, "ab", "https://example.com/a b");
+        scr.print_link(2, 0, "c", "https://example.org");
+
+        let snapshot = scr.to_snapshot();
+        assert_eq!(
+            snapshot,
+            "screen 4x1\n\
+             |abc |\n\
+             styles\n\
+             |AABC|\n\
+             legend\n\
+             A Reset Reset - link https://example.com/a b\n\
+             B Reset Reset - link https://example.org\n\
+             C Reset Reset -\n"
+        );
+        let parsed = Screen::from_snapshot(&snapshot).unwrap();
+        assert!(super::diff(&scr, &parsed).is_empty());
+        assert_eq!(parsed.get_link(1, 0), Some("https://example.com/a b"));
+
+        // pixels only differing by their link are reported
+        scr.print(2, 0, "c");
+        let diff = super::diff(&parsed, &scr);
+        assert_eq!(diff.cells.len(), 1);
+        assert_eq!(
+            diff.to_string(),
+            "[2, 0]: expected 'c' (fg: Reset, bg: Reset, style: -, link: https://example.org), \
+             got 'c' (fg: Reset, bg: Reset, style: -)\n"
+        );
+
+        // targets can't break the lines of the legend
+        let mut scr = Screen::new(2, 1);
+        scr.print_link(0, 0, "a", "x\nlink y\\z\r");
+        scr.print_link(1, 0, "b", " link \\u{41}");
+        let snapshot = scr.to_snapshot();
+        assert!(snapshot.ends_with(
+            "legend\n\
+             A Reset Reset - link x\\u{a}link y\\\\z\\u{d}\n\
+             B Reset Reset - link  link \\\\u{41}\n"
+        ));
+        let parsed = Screen::from_snapshot(&snapshot).unwrap();
+        assert!(super::diff(&scr, &parsed).is_empty());
+        assert_eq!(parsed.get_link(0, 0), Some("x\nlink y\\z\r"));
+        assert_eq!(parsed.get_link(1, 0), Some(" link \\u{41}"));
+    }
+
+    #[test]
+    fn many_styles() {
+        let mut scr = Screen::new(40, 20);
+        for y in 0..20 {
+            for x in 0..40 {
+                let color = Color::Rgb {
+                    r: x as u8 * 6,
+                    g: y as u8 * 12,
+                    b: 128,
+                };
+                scr.set_pxl(x, y, pixel::pxl('#', Some(color), None, None));
+            }
+        }
+        let snapshot = scr.to_snapshot();
+        let parsed = Screen::from_snapshot(&snapshot).unwrap();
+        assert!(super::diff(&scr, &parsed).is_empty());
+
+        let keys: Vec<char> = super::legend_keys().take(5000).collect();
+        let mut unique = keys.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), keys.len());
+    }
+
+    #[test]
+    fn invalid_snapshots() {
+        assert!(Screen::from_snapshot("").is_err());
+        assert!(
+            Screen::from_snapshot("screen 2x1\n|ab|\nstyles\n|AB|\nlegend\nA Reset Reset -\n")
+                .is_err()
+        );
+        assert!(Screen::from_snapshot(
+            "screen 2x1\n|abc|\nstyles\n|AA|\nlegend\nA Reset Reset -\n"
+        )
+        .is_err());
+        assert!(
+            Screen::from_snapshot("screen 2x1\n|ab|\nstyles\n|AA|\nlegend\nA Pink Reset -\n")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn diff() {
+        let mut expected = Screen::new(4, 2);
+        expected.print(0, 0, "abcd");
+        let mut actual = expected.clone();
+        assert!(super::diff(&expected, &actual).is_empty());
+
+        actual.set_pxl(1, 0, pixel::pxl('x', Some(Color::Blue), None, None));
+        actual.print(3, 1, "y");
+        let diff = super::diff(&expected, &actual);
+        assert_eq!(diff.cells.len(), 2);
+        assert_eq!(
+            diff.to_string(),
+            "[1, 0]: expected 'b' (fg: Reset, bg: Reset, style: -), got 'x' (fg: Blue, bg: Reset, style: -)\n\
+             [3, 1]: expected ' ' (fg: Reset, bg: Reset, style: -), got 'y' (fg: Reset, bg: Reset, style: -)\n"
+        );
+
+        let diff = super::diff(&expected, &Screen::new(5, 2));
+        assert!(diff
+            .to_string()
+            .starts_with("size: expected 4x2, got 5x2\n"));
+    }
+}
@@ -1,3 +1,5 @@
+use crossterm::event::KeyCode;
+
 /// Returns a vector containing the values of both arrays, keeping the values unique
 pub fn union<T: PartialEq + Clone>(vector1: &[T], vector2: &[T]) -> Vec<T> {
     let mut unioned: Vec<T> = vec![];
@@ -49,3 +51,78 @@ pub fn outersect_left<T: PartialEq + Clone>(vector1: &[T], vector2: &[T]) -> Vec
     }
     outersected
 }
+
+/// Checks if two key codes refer to the same physical key, ignoring the case of characters
+pub fn same_key(key1: KeyCode, key2: KeyCode) -> bool {
+    match (key1, key2) {
+        (KeyCode::Char(c1), KeyCode::Char(c2)) => c1.to_lowercase().eq(c2.to_lowercase()),
+        _ => key1 == key2,
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes in base64 (standard alphabet, with padding)
+pub fn base64_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let group = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let value = (group >> (18 - 6 * index)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[value as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decodes base64 text (standard alphabet, padding is optional)
+///
+/// Returns `None` if the text contains anything else than base64 characters
+pub fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(text.len() / 4 * 3);
+    let mut group = 0u32;
+    let mut bits = 0;
+    for byte in text.trim_end_matches('=').bytes() {
+        let value = BASE64_ALPHABET.iter().position(|b| *b == byte)? as u32;
+        group = group << 6 | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((group >> bits) as u8);
+            group &= (1 << bits) - 1;
+        }
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{base64_decode, base64_encode};
+
+    #[test]
+    fn base64() {
+        for (data, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("héllo?>", "aMOpbGxvPz4="),
+        ] {
+            assert_eq!(base64_encode(data.as_bytes()), encoded);
+            assert_eq!(base64_decode(encoded).unwrap(), data.as_bytes());
+        }
+        assert_eq!(base64_decode("Zm9vYg").unwrap(), b"foob");
+        assert_eq!(base64_decode("Zm9v!"), None);
+    }
+}
@@ -20,7 +20,6 @@ struct Window {
     width: i32,
     height: i32,
     contents: Screen,
-    drag_starting_pos: (i32, i32),
     moving: bool,
     orig_window_pos: (i32, i32),
     resize_dir: Option<ResizeDir>,
@@ -54,6 +53,11 @@ pub struct WindowManager {
     windows: Vec<Window>,
     focus_order: Vec<usize>,
 }
+impl Default for WindowManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl WindowManager {
     pub fn new() -> Self {
         Self {
@@ -73,7 +77,6 @@ impl WindowManager {
                 height as u32 - 3,
                 pxl(' ', None, None, None),
             ),
-            drag_starting_pos: (0, 0),
             moving: false,
             orig_window_pos: (0, 0),
             resize_dir: None,
@@ -148,7 +151,6 @@ impl WindowManager {
                             continue;
                         } else {
                             w.moving = true;
-                            w.drag_starting_pos = (mouse_pos.0 as i32, mouse_pos.1 as i32);
                             w.orig_window_pos = (w.x, w.y);
                         }
                     }
@@ -157,7 +159,6 @@ impl WindowManager {
                         && mouse_pos.1 as i32 == whole_window_area.3
                     {
                         w.resize_dir = Some(ResizeDir::LeftCorner);
-                        w.drag_starting_pos = (mouse_pos.0 as i32, mouse_pos.1 as i32);
                         w.orig_window_size = (w.width, w.height);
                         w.orig_window_pos = (w.x, w.y);
                     }
@@ -166,7 +167,6 @@ impl WindowManager {
                         && mouse_pos.1 as i32 == whole_window_area.3
                     {
                         w.resize_dir = Some(ResizeDir::RightCorner);
-                        w.drag_starting_pos = (mouse_pos.0 as i32, mouse_pos.1 as i32);
                         w.orig_window_size = (w.width, w.height);
                     }
                     // Check if mouse_pos is on the bottom border of the window
@@ -175,7 +175,6 @@ impl WindowManager {
                         && mouse_pos.0 as i32 <= whole_window_area.2
                     {
                         w.resize_dir = Some(ResizeDir::Bottom);
-                        w.drag_starting_pos = (mouse_pos.0 as i32, mouse_pos.1 as i32);
                         w.orig_window_size = (w.width, w.height);
                     }
                     // Check if mouse_pos is on the left border of the window
@@ -184,7 +183,6 @@ impl WindowManager {
                         && mouse_pos.1 as i32 <= whole_window_area.3
                     {
                         w.resize_dir = Some(ResizeDir::Left);
-                        w.drag_starting_pos = (mouse_pos.0 as i32, mouse_pos.1 as i32);
                         w.orig_window_size = (w.width, w.height);
                         w.orig_window_pos = (w.x, w.y);
                     }
@@ -194,41 +192,41 @@ impl WindowManager {
                         && mouse_pos.1 as i32 <= whole_window_area.3
                     {
                         w.resize_dir = Some(ResizeDir::Right);
-                        w.drag_starting_pos = (mouse_pos.0 as i32, mouse_pos.1 as i32);
                         w.orig_window_size = (w.width, w.height);
                     }
                     break;
                 }
             }
 
-            // Left-mouse hold
-            if let Some(mouse_pos) = engine.get_mouse_held(crossterm::event::MouseButton::Left) {
+            // Left-mouse drag, relative to where the mouse has been pressed
+            if let Some(drag) = engine
+                .get_drag()
+                .filter(|drag| drag.button == crossterm::event::MouseButton::Left)
+            {
+                let (dx, dy) = drag.get_offset();
                 if w.moving {
-                    w.x = w.orig_window_pos.0 + mouse_pos.0 as i32 - w.drag_starting_pos.0;
-                    w.y = w.orig_window_pos.1 + mouse_pos.1 as i32 - w.drag_starting_pos.1;
+                    w.x = w.orig_window_pos.0 + dx;
+                    w.y = w.orig_window_pos.1 + dy;
                 }
                 match w.resize_dir {
                     Some(ResizeDir::Left) => {
-                        w.width = (w.orig_window_size.0 - mouse_pos.0 as i32 + w.drag_starting_pos.0).max(6);
-                        w.x = w.orig_window_pos.0 + mouse_pos.0 as i32 - w.drag_starting_pos.0;
+                        w.width = (w.orig_window_size.0 - dx).max(6);
+                        w.x = w.orig_window_pos.0 + dx;
                     }
                     Some(ResizeDir::Right) => {
-                        w.width = (w.orig_window_size.0 + mouse_pos.0 as i32 - w.drag_starting_pos.0).max(6);
+                        w.width = (w.orig_window_size.0 + dx).max(6);
                     }
                     Some(ResizeDir::Bottom) => {
-                        w.height =
-                            (w.orig_window_size.1 + mouse_pos.1 as i32 - w.drag_starting_pos.1).max(4);
+                        w.height = (w.orig_window_size.1 + dy).max(4);
                     }
                     Some(ResizeDir::LeftCorner) => {
-                        w.width = (w.orig_window_size.0 - mouse_pos.0 as i32 + w.drag_starting_pos.0).max(6);
-                        w.height =
-                            (w.orig_window_size.1 + mouse_pos.1 as i32 - w.drag_starting_pos.1).max(4);
-                        w.x = w.orig_window_pos.0 + mouse_pos.0 as i32 - w.drag_starting_pos.0;
+                        w.width = (w.orig_window_size.0 - dx).max(6);
+                        w.height = (w.orig_window_size.1 + dy).max(4);
+                        w.x = w.orig_window_pos.0 + dx;
                     }
                     Some(ResizeDir::RightCorner) => {
-                        w.width = (w.orig_window_size.0 + mouse_pos.0 as i32 - w.drag_starting_pos.0).max(6);
-                        w.height =
-                            (w.orig_window_size.1 + mouse_pos.1 as i32 - w.drag_starting_pos.1).max(4);
+                        w.width = (w.orig_window_size.0 + dx).max(6);
+                        w.height = (w.orig_window_size.1 + dy).max(4);
                     }
                     _ => {}
                 }